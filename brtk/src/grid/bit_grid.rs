@@ -0,0 +1,375 @@
+use bitvec::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::grid::{Grid, GridCoord, GridSizeError};
+
+/// A `Grid` of booleans packed into individual bits.
+///
+/// Shares the position API of `Grid<T>` and adds bulk operations (fill, union, intersect,
+/// counting) that work a machine word at a time.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "BitGridData")]
+pub struct BitGrid {
+    size: (usize, usize),
+    bits: BitVec,
+}
+
+/// Unchecked serialized form of a `BitGrid`, validated through [`BitGrid::from_bits`]
+#[derive(Deserialize)]
+struct BitGridData {
+    size: (usize, usize),
+    bits: BitVec,
+}
+
+impl TryFrom<BitGridData> for BitGrid {
+    type Error = GridSizeError;
+
+    fn try_from(data: BitGridData) -> Result<Self, Self::Error> {
+        Self::from_bits(data.size, data.bits)
+    }
+}
+
+// Constructors
+impl BitGrid {
+    /// Create a new `BitGrid` from a `(width, height)` with every bit cleared
+    pub fn new(size: (usize, usize)) -> Self {
+        Self::new_fill(size, false)
+    }
+
+    /// Create a new `BitGrid` filled with the specified value
+    pub fn new_fill(size: (usize, usize), value: bool) -> Self {
+        Self { size, bits: BitVec::repeat(value, size.0 * size.1) }
+    }
+
+    /// Create a new `BitGrid` from a `(width, height)` and its bits in row-major order.
+    /// Fails if the number of bits doesn't match width * height.
+    pub fn from_bits(size: (usize, usize), bits: BitVec) -> Result<Self, GridSizeError> {
+        let expected = size.0 * size.1;
+        if bits.len() != expected {
+            return Err(GridSizeError::DataLength { len: bits.len(), expected });
+        }
+
+        Ok(Self { size, bits })
+    }
+
+    /// Create a new `BitGrid` from a `(width, height)` obtaining a value from a `Fn(index, position) ->
+    /// bool`. Uses row-major order (iterates over rows first, then columns).
+    pub fn new_fn(size: (usize, usize), mut f: impl FnMut(usize, (usize, usize)) -> bool) -> Self {
+        let mut bits = BitVec::with_capacity(size.0 * size.1);
+        let mut idx = 0;
+
+        for y in 0..size.1 {
+            for x in 0..size.0 {
+                bits.push(f(idx, (x, y)));
+                idx += 1;
+            }
+        }
+
+        Self { size, bits }
+    }
+
+    /// Create a new `BitGrid` with the size of `grid`, setting every cell for which `f` is true
+    pub fn from_grid<T>(grid: &Grid<T>, mut f: impl FnMut(&T) -> bool) -> Self {
+        Self { size: grid.size(), bits: grid.iter().map(&mut f).collect() }
+    }
+}
+
+impl BitGrid {
+    /// Obtain the size of this `BitGrid`
+    #[inline]
+    pub const fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// Obtain the width of this `BitGrid`
+    #[inline]
+    pub const fn width(&self) -> usize {
+        self.size.0
+    }
+
+    /// Obtain the height of this `BitGrid`
+    #[inline]
+    pub const fn height(&self) -> usize {
+        self.size.1
+    }
+
+    /// Determine if a position is inside of this `BitGrid`
+    #[inline]
+    pub fn in_bounds(&self, position: impl GridCoord) -> bool {
+        self.in_bounds_xy(position.grid_position())
+    }
+
+    #[inline]
+    const fn in_bounds_xy(&self, position: (i32, i32)) -> bool {
+        position.0 >= 0
+            && position.0 < self.width() as i32
+            && position.1 >= 0
+            && position.1 < self.height() as i32
+    }
+
+    /// Determine if an index is valid in this `BitGrid`
+    #[inline]
+    pub fn is_valid(&self, index: usize) -> bool {
+        index < self.bits.len()
+    }
+}
+
+// Index/Position Conversion
+impl BitGrid {
+    /// Converts a position into an index
+    pub fn position_to_index(&self, position: impl GridCoord) -> Option<usize> {
+        if self.in_bounds(position) {
+            Some(self.position_to_index_unchecked(position))
+        } else {
+            None
+        }
+    }
+
+    /// Converts a position into an index
+    #[inline]
+    pub fn position_to_index_unchecked(&self, position: impl GridCoord) -> usize {
+        let (x, y) = position.grid_position();
+        (y * self.width() as i32 + x) as usize
+    }
+
+    /// Converts an index into a position
+    pub const fn index_to_position(&self, index: usize) -> Option<(i32, i32)> {
+        let position = self.index_to_position_unchecked(index);
+        if self.in_bounds_xy(position) {
+            Some(position)
+        } else {
+            None
+        }
+    }
+
+    /// Converts an index into a position
+    #[inline]
+    pub const fn index_to_position_unchecked(&self, index: usize) -> (i32, i32) {
+        ((index % self.width()) as i32, (index / self.width()) as i32)
+    }
+}
+
+// Accessors
+impl BitGrid {
+    /// Borrow the underlying bits
+    #[inline]
+    pub fn bits(&self) -> &BitSlice {
+        &self.bits
+    }
+
+    /// Mutably borrow the underlying bits
+    #[inline]
+    pub fn bits_mut(&mut self) -> &mut BitSlice {
+        &mut self.bits
+    }
+
+    /// Obtain the value at an index
+    #[inline]
+    pub fn get_index(&self, index: usize) -> Option<bool> {
+        self.bits.get(index).map(|bit| *bit)
+    }
+
+    /// Set the value at an index, returning false if the index is invalid
+    #[inline]
+    pub fn set_index(&mut self, index: usize, value: bool) -> bool {
+        if self.is_valid(index) {
+            self.bits.set(index, value);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Obtain the value at a position
+    pub fn get(&self, position: impl GridCoord) -> Option<bool> {
+        self.position_to_index(position).map(|index| self.bits[index])
+    }
+
+    /// Obtain the value at a position, treating out of bounds positions as cleared
+    #[inline]
+    pub fn is_set(&self, position: impl GridCoord) -> bool {
+        self.get(position).unwrap_or(false)
+    }
+
+    /// Set the value at a position, returning false if the position is out of bounds
+    pub fn set(&mut self, position: impl GridCoord, value: bool) -> bool {
+        match self.position_to_index(position) {
+            Some(index) => {
+                self.bits.set(index, value);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// Bulk operations
+impl BitGrid {
+    /// Set every cell to `value`
+    pub fn fill(&mut self, value: bool) {
+        self.bits.fill(value);
+    }
+
+    /// Set every cell that is set in `other`.
+    /// Panics if the grids differ in size.
+    pub fn union_with(&mut self, other: &BitGrid) {
+        self.assert_same_size(other);
+        self.bits |= other.bits.as_bitslice();
+    }
+
+    /// Clear every cell that is not set in `other`.
+    /// Panics if the grids differ in size.
+    pub fn intersect_with(&mut self, other: &BitGrid) {
+        self.assert_same_size(other);
+        self.bits &= other.bits.as_bitslice();
+    }
+
+    /// Clear every cell that is set in `other`.
+    /// Panics if the grids differ in size.
+    pub fn difference_with(&mut self, other: &BitGrid) {
+        self.assert_same_size(other);
+        for (mut bit, other_bit) in self.bits.iter_mut().zip(other.bits.iter()) {
+            *bit &= !*other_bit;
+        }
+    }
+
+    /// Flip every cell
+    pub fn invert(&mut self) {
+        let bits = std::mem::take(&mut self.bits);
+        self.bits = !bits;
+    }
+
+    /// Number of set cells
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        self.bits.count_ones()
+    }
+
+    /// Number of cleared cells
+    #[inline]
+    pub fn count_zeros(&self) -> usize {
+        self.bits.count_zeros()
+    }
+
+    /// Determine if any cell is set
+    #[inline]
+    pub fn any(&self) -> bool {
+        self.bits.any()
+    }
+
+    /// Determine if every cell is set
+    #[inline]
+    pub fn all(&self) -> bool {
+        self.bits.all()
+    }
+
+    fn assert_same_size(&self, other: &BitGrid) {
+        assert_eq!(self.size, other.size, "Bit grids must have the same dimensions");
+    }
+}
+
+// Iterators
+impl BitGrid {
+    /// Iterate over every value in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        self.bits.iter().by_vals()
+    }
+
+    /// Iterate over the position and value of every cell in row-major order
+    pub fn iter_with_positions(&self) -> impl Iterator<Item = ((i32, i32), bool)> + '_ {
+        self.iter().enumerate().map(|(index, value)| (self.index_to_position_unchecked(index), value))
+    }
+
+    /// Iterate over the positions of every set cell in row-major order
+    pub fn iter_ones(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.bits.iter_ones().map(|index| self.index_to_position_unchecked(index))
+    }
+
+    /// Iterate over the positions of every cleared cell in row-major order
+    pub fn iter_zeros(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.bits.iter_zeros().map(|index| self.index_to_position_unchecked(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 9 * 9 = 81 cells, so the last machine word is only partly used
+    const SIZE: (usize, usize) = (9, 9);
+
+    fn diagonal() -> BitGrid {
+        BitGrid::new_fn(SIZE, |_, (x, y)| x == y)
+    }
+
+    #[test]
+    fn fill_sets_every_cell_and_no_tail_bits() {
+        let mut grid = BitGrid::new(SIZE);
+        assert!(!grid.any());
+
+        grid.fill(true);
+        assert!(grid.all());
+        assert_eq!(grid.count_ones(), 81);
+        assert_eq!(grid.count_zeros(), 0);
+
+        grid.invert();
+        assert!(!grid.any());
+        assert_eq!(grid.count_zeros(), 81);
+    }
+
+    #[test]
+    fn union_and_intersect() {
+        let mut row = BitGrid::new_fn(SIZE, |_, (_, y)| y == 8);
+        let mut union = diagonal();
+        union.union_with(&row);
+        assert_eq!(union.count_ones(), 9 + 9 - 1);
+        assert!(union.is_set((3, 3)) && union.is_set((0, 8)));
+
+        row.intersect_with(&diagonal());
+        assert_eq!(row.iter_ones().collect::<Vec<_>>(), vec![(8, 8)]);
+
+        union.difference_with(&diagonal());
+        assert_eq!(union.count_ones(), 8);
+        assert!(!union.is_set((8, 8)));
+    }
+
+    #[test]
+    #[should_panic(expected = "same dimensions")]
+    fn union_of_different_sizes_panics() {
+        BitGrid::new((3, 3)).union_with(&BitGrid::new((3, 4)));
+    }
+
+    #[test]
+    fn iter_ones_yields_set_positions_in_row_major_order() {
+        let mut grid = BitGrid::new(SIZE);
+        for position in [(8, 8), (2, 0), (0, 5)] {
+            assert!(grid.set(position, true));
+        }
+        assert!(!grid.set((9, 0), true));
+
+        assert_eq!(grid.iter_ones().collect::<Vec<_>>(), vec![(2, 0), (0, 5), (8, 8)]);
+        assert_eq!(grid.iter_zeros().count(), 78);
+        assert_eq!(grid.get((8, 8)), Some(true));
+        assert_eq!(grid.get((-1, 8)), None);
+    }
+
+    #[test]
+    fn deserialize_round_trips() {
+        let grid = diagonal();
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<BitGrid>(&json).unwrap(), grid);
+    }
+
+    #[test]
+    fn deserialize_rejects_a_bit_count_mismatch() {
+        let json = serde_json::to_string(&BitGrid::new((4, 4))).unwrap();
+        let json = json.replace("[4,4]", "[4,5]");
+        let error = serde_json::from_str::<BitGrid>(&json).unwrap_err();
+        assert!(error.to_string().contains("expected 20"), "{error}");
+
+        assert_eq!(
+            BitGrid::from_bits((2, 2), BitVec::repeat(false, 3)),
+            Err(GridSizeError::DataLength { len: 3, expected: 4 })
+        );
+    }
+}
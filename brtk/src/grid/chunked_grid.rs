@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::grid::{Grid, GridCoord, GridRect, GridSizeError};
+
+/// An unbounded grid made of fixed-size `Grid<T>` chunks, addressed by signed coordinates.
+///
+/// Chunks are created lazily the first time a cell inside of them is written to. Reading a cell
+/// whose chunk does not exist yields the default value, so sparse or streamed maps only pay for
+/// the regions that were actually generated. Each chunk is a plain `Grid<T>` and can be
+/// serialized on its own with [`chunk`](Self::chunk) and [`insert_chunk`](Self::insert_chunk).
+///
+/// The whole grid serializes its chunks as a list of `(chunk_coord, chunk)` pairs, so it also
+/// works with formats that only allow string map keys, such as JSON.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(try_from = "ChunkedGridData<T>")]
+pub struct ChunkedGrid<T> {
+    chunk_size: (usize, usize),
+    default: T,
+    #[serde(serialize_with = "serialize_chunks")]
+    chunks: HashMap<(i32, i32), Grid<T>>,
+}
+
+/// Unchecked serialized form of a `ChunkedGrid`, validated chunk by chunk on deserialize
+#[derive(Deserialize)]
+struct ChunkedGridData<T> {
+    chunk_size: (usize, usize),
+    default: T,
+    chunks: Vec<((i32, i32), Grid<T>)>,
+}
+
+impl<T> TryFrom<ChunkedGridData<T>> for ChunkedGrid<T> {
+    type Error = GridSizeError;
+
+    fn try_from(data: ChunkedGridData<T>) -> Result<Self, Self::Error> {
+        if data.chunk_size.0 == 0 || data.chunk_size.1 == 0 {
+            return Err(GridSizeError::EmptyChunkSize);
+        }
+
+        let mut grid = Self::new(data.chunk_size, data.default);
+        for (chunk_coord, chunk) in data.chunks {
+            grid.insert_chunk(chunk_coord, chunk)?;
+        }
+
+        Ok(grid)
+    }
+}
+
+/// Write chunks sorted by coordinate so the same grid always serializes the same way
+fn serialize_chunks<T: Serialize, S: Serializer>(
+    chunks: &HashMap<(i32, i32), Grid<T>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut chunks: Vec<_> = chunks.iter().collect();
+    chunks.sort_unstable_by_key(|(chunk_coord, _)| **chunk_coord);
+    serializer.collect_seq(chunks)
+}
+
+// Constructors
+impl<T> ChunkedGrid<T> {
+    /// Create a new, empty `ChunkedGrid` with chunks of `(width, height)` cells.
+    /// Panics if either dimension of the chunk size is zero.
+    pub fn new(chunk_size: (usize, usize), default: T) -> Self {
+        assert!(chunk_size.0 > 0 && chunk_size.1 > 0, "Chunk dimensions must be non-zero");
+        Self { chunk_size, default, chunks: HashMap::new() }
+    }
+}
+
+impl<T> ChunkedGrid<T> {
+    /// Obtain the size of each chunk
+    #[inline]
+    pub const fn chunk_size(&self) -> (usize, usize) {
+        self.chunk_size
+    }
+
+    /// Borrow the value returned for cells of chunks that do not exist
+    #[inline]
+    pub const fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    /// Number of chunks that currently exist
+    #[inline]
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Determine if no chunk exists yet
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+}
+
+// Chunk/Position Conversion
+impl<T> ChunkedGrid<T> {
+    /// Converts a position into the coordinate of the chunk containing it
+    pub fn chunk_coord(&self, position: impl GridCoord) -> (i32, i32) {
+        let (x, y) = position.grid_position();
+        (x.div_euclid(self.chunk_size.0 as i32), y.div_euclid(self.chunk_size.1 as i32))
+    }
+
+    /// Converts a position into a position local to the chunk containing it
+    pub fn local_position(&self, position: impl GridCoord) -> (i32, i32) {
+        let (x, y) = position.grid_position();
+        (x.rem_euclid(self.chunk_size.0 as i32), y.rem_euclid(self.chunk_size.1 as i32))
+    }
+
+    /// The rect of positions covered by the chunk at `chunk_coord`
+    pub const fn chunk_rect(&self, chunk_coord: (i32, i32)) -> GridRect {
+        let (width, height) = (self.chunk_size.0 as i32, self.chunk_size.1 as i32);
+        GridRect::new(chunk_coord.0 * width, chunk_coord.1 * height, width, height)
+    }
+}
+
+// Accessors
+impl<T> ChunkedGrid<T> {
+    /// Borrow a value at a position, or the default value if its chunk does not exist
+    pub fn get(&self, position: impl GridCoord) -> &T {
+        self.chunks
+            .get(&self.chunk_coord(position))
+            .and_then(|chunk| chunk.get(self.local_position(position)))
+            .unwrap_or(&self.default)
+    }
+
+    /// Mutably borrow a value at a position, creating its chunk if needed
+    pub fn get_mut(&mut self, position: impl GridCoord) -> &mut T
+    where
+        T: Clone,
+    {
+        let local = self.local_position(position);
+        let chunk = self.get_or_create_chunk(self.chunk_coord(position));
+        &mut chunk[local]
+    }
+
+    /// Set a value at a position, creating its chunk if needed
+    pub fn set(&mut self, position: impl GridCoord, value: T)
+    where
+        T: Clone,
+    {
+        *self.get_mut(position) = value;
+    }
+
+    /// Borrow the chunk at `chunk_coord`, if it exists
+    pub fn chunk(&self, chunk_coord: (i32, i32)) -> Option<&Grid<T>> {
+        self.chunks.get(&chunk_coord)
+    }
+
+    /// Mutably borrow the chunk at `chunk_coord`, if it exists
+    pub fn chunk_mut(&mut self, chunk_coord: (i32, i32)) -> Option<&mut Grid<T>> {
+        self.chunks.get_mut(&chunk_coord)
+    }
+
+    /// Mutably borrow the chunk at `chunk_coord`, filling a new one with the default value if it
+    /// does not exist
+    pub fn get_or_create_chunk(&mut self, chunk_coord: (i32, i32)) -> &mut Grid<T>
+    where
+        T: Clone,
+    {
+        let (chunk_size, default) = (self.chunk_size, &self.default);
+        self.chunks.entry(chunk_coord).or_insert_with(|| Grid::new_fill(chunk_size, default.clone()))
+    }
+
+    /// Mutably borrow the chunk at `chunk_coord`, generating a new one with `f` if it does not
+    /// exist. `f` receives the rect of positions the chunk will cover.
+    pub fn get_or_generate_chunk(
+        &mut self,
+        chunk_coord: (i32, i32),
+        f: impl FnOnce(GridRect) -> Grid<T>,
+    ) -> &mut Grid<T> {
+        let rect = self.chunk_rect(chunk_coord);
+        let chunk_size = self.chunk_size;
+        self.chunks.entry(chunk_coord).or_insert_with(|| {
+            let chunk = f(rect);
+            assert_eq!(chunk.size(), chunk_size, "Generated chunk must match the chunk size");
+            chunk
+        })
+    }
+
+    /// Insert a chunk, for example one that was just streamed in, returning the chunk it replaced.
+    /// Fails without inserting anything if the chunk does not match the chunk size.
+    pub fn insert_chunk(
+        &mut self,
+        chunk_coord: (i32, i32),
+        chunk: Grid<T>,
+    ) -> Result<Option<Grid<T>>, GridSizeError> {
+        if chunk.size() != self.chunk_size {
+            return Err(GridSizeError::ChunkSize { size: chunk.size(), expected: self.chunk_size });
+        }
+
+        let expected = self.chunk_size.0 * self.chunk_size.1;
+        if chunk.data().len() != expected {
+            return Err(GridSizeError::DataLength { len: chunk.data().len(), expected });
+        }
+
+        Ok(self.chunks.insert(chunk_coord, chunk))
+    }
+
+    /// Remove a chunk, for example to stream it out, returning it if it existed
+    pub fn remove_chunk(&mut self, chunk_coord: (i32, i32)) -> Option<Grid<T>> {
+        self.chunks.remove(&chunk_coord)
+    }
+}
+
+// Iterators
+impl<T> ChunkedGrid<T> {
+    /// Iterate over the coordinates of every existing chunk, in no particular order
+    pub fn chunk_coords(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// Iterate over every existing chunk and its coordinate, in no particular order
+    pub fn chunks(&self) -> impl Iterator<Item = ((i32, i32), &Grid<T>)> {
+        self.chunks.iter().map(|(&chunk_coord, chunk)| (chunk_coord, chunk))
+    }
+
+    /// Mutably iterate over every existing chunk and its coordinate, in no particular order
+    pub fn chunks_mut(&mut self) -> impl Iterator<Item = ((i32, i32), &mut Grid<T>)> {
+        self.chunks.iter_mut().map(|(&chunk_coord, chunk)| (chunk_coord, chunk))
+    }
+
+    /// Iterate over the position and value of every cell of every existing chunk
+    pub fn iter_with_positions(&self) -> impl Iterator<Item = ((i32, i32), &T)> {
+        self.chunks().flat_map(move |(chunk_coord, chunk)| {
+            let rect = self.chunk_rect(chunk_coord);
+            chunk.iter_with_positions().map(move |((x, y), value)| ((x + rect.x, y + rect.y), value))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> ChunkedGrid<u8> {
+        ChunkedGrid::new((4, 3), 0)
+    }
+
+    #[test]
+    fn negative_positions_map_to_the_chunk_below() {
+        let grid = grid();
+        assert_eq!(grid.chunk_coord((0, 0)), (0, 0));
+        assert_eq!(grid.chunk_coord((-1, -1)), (-1, -1));
+        assert_eq!(grid.local_position((-1, -1)), (3, 2));
+        assert_eq!(grid.chunk_coord((-4, -3)), (-1, -1));
+        assert_eq!(grid.local_position((-4, -3)), (0, 0));
+        assert_eq!(grid.chunk_coord((-5, 3)), (-2, 1));
+        assert_eq!(grid.chunk_rect((-2, 1)), GridRect::new(-8, 3, 4, 3));
+    }
+
+    #[test]
+    fn writes_across_chunk_borders_create_chunks_lazily() {
+        let mut grid = grid();
+        assert!(grid.is_empty());
+
+        grid.set((-1, 0), 1);
+        grid.set((0, 0), 2);
+        grid.set((3, -1), 3);
+        grid.set((2, -2), 4);
+        assert_eq!(grid.chunk_count(), 3);
+
+        assert_eq!(*grid.get((-1, 0)), 1);
+        assert_eq!(*grid.get((0, 0)), 2);
+        assert_eq!(*grid.get((3, -1)), 3);
+        assert_eq!(grid.chunk((0, -1)).map(|chunk| chunk[(2, 1)]), Some(4));
+    }
+
+    #[test]
+    fn absent_cells_read_the_default() {
+        let mut grid = ChunkedGrid::new((4, 3), 7_u8);
+        assert_eq!(*grid.get((100, -100)), 7);
+        assert!(grid.is_empty());
+
+        grid.set((1, 1), 9);
+        assert_eq!(*grid.get((2, 2)), 7);
+        assert_eq!(*grid.get((5, 1)), 7);
+        assert_eq!(grid.chunk_count(), 1);
+    }
+
+    #[test]
+    fn insert_rejects_mismatched_chunks() {
+        let mut grid = grid();
+        assert_eq!(
+            grid.insert_chunk((0, 0), Grid::new_fill((3, 4), 1)).unwrap_err(),
+            GridSizeError::ChunkSize { size: (3, 4), expected: (4, 3) }
+        );
+        assert!(grid.is_empty());
+
+        assert!(grid.insert_chunk((0, 0), Grid::new_fill((4, 3), 1)).unwrap().is_none());
+        assert_eq!(*grid.get((3, 2)), 1);
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let mut grid = grid();
+        for (position, value) in [((-1, -1), 1), ((0, 0), 2), ((9, -7), 3)] {
+            grid.set(position, value);
+        }
+
+        let json = serde_json::to_string(&grid).unwrap();
+        let loaded: ChunkedGrid<u8> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.chunk_size(), (4, 3));
+        assert_eq!(loaded.chunk_count(), 3);
+        let mut cells: Vec<_> = loaded.iter_with_positions().filter(|(_, &value)| value != 0).collect();
+        cells.sort_unstable();
+        assert_eq!(cells, vec![((-1, -1), &1), ((0, 0), &2), ((9, -7), &3)]);
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    }
+
+    #[test]
+    fn deserialize_rejects_mismatched_chunks() {
+        let mut grid = grid();
+        grid.set((0, 0), 1);
+        let json = serde_json::to_string(&grid).unwrap();
+
+        let resized = json.replacen("[4,3]", "[3,4]", 1);
+        assert!(serde_json::from_str::<ChunkedGrid<u8>>(&resized).is_err());
+
+        let empty = json.replacen("[4,3]", "[0,3]", 1);
+        assert!(serde_json::from_str::<ChunkedGrid<u8>>(&empty).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Offsets of the four cardinal neighbors of a cell
+pub const CARDINAL_OFFSETS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets of the four diagonal neighbors of a cell
+pub const DIAGONAL_OFFSETS: [(i32, i32); 4] = [(1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+/// Offsets of all eight neighbors of a cell, cardinals first
+pub const ALL_OFFSETS: [(i32, i32); 8] =
+    [(0, -1), (1, 0), (0, 1), (-1, 0), (1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+/// Which cells count as adjacent when moving across a `Grid`
+#[derive(Serialize, Deserialize, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// Cardinal neighbors only (von Neumann neighborhood)
+    #[default]
+    Four,
+    /// Cardinal and diagonal neighbors (Moore neighborhood)
+    Eight,
+}
+
+impl Connectivity {
+    /// The neighbor offsets for this connectivity
+    #[inline]
+    pub const fn offsets(&self) -> &'static [(i32, i32)] {
+        match self {
+            Connectivity::Four => &CARDINAL_OFFSETS,
+            Connectivity::Eight => &ALL_OFFSETS,
+        }
+    }
+
+    /// Estimate the number of steps between two positions, never overestimating
+    ///
+    /// Uses Manhattan distance for `Four` and Chebyshev distance for `Eight`.
+    #[inline]
+    pub const fn distance(&self, a: (i32, i32), b: (i32, i32)) -> u32 {
+        let dx = a.0.abs_diff(b.0);
+        let dy = a.1.abs_diff(b.1);
+        match self {
+            Connectivity::Four => dx + dy,
+            Connectivity::Eight => {
+                if dx > dy {
+                    dx
+                } else {
+                    dy
+                }
+            }
+        }
+    }
+}
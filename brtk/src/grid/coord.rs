@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+
+/// A coordinate type that can address a cell of a `Grid` or `BitGrid`.
+///
+/// Implemented for integer pairs and Bevy's integer vectors. Downstream crates can implement it
+/// for their own position types so they can index grids without converting first.
+pub trait GridCoord: Copy {
+    /// Convert into the `(x, y)` pair used internally by the grids
+    fn grid_position(self) -> (i32, i32);
+}
+
+impl GridCoord for (i32, i32) {
+    #[inline]
+    fn grid_position(self) -> (i32, i32) {
+        self
+    }
+}
+
+impl GridCoord for (u32, u32) {
+    #[inline]
+    fn grid_position(self) -> (i32, i32) {
+        (self.0 as i32, self.1 as i32)
+    }
+}
+
+impl GridCoord for (usize, usize) {
+    #[inline]
+    fn grid_position(self) -> (i32, i32) {
+        (self.0 as i32, self.1 as i32)
+    }
+}
+
+impl GridCoord for IVec2 {
+    #[inline]
+    fn grid_position(self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+}
+
+impl GridCoord for UVec2 {
+    #[inline]
+    fn grid_position(self) -> (i32, i32) {
+        (self.x as i32, self.y as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::{BitGrid, Grid};
+
+    use super::*;
+
+    fn grid() -> Grid<usize> {
+        Grid::new_fn((4, 3), |index, _| index)
+    }
+
+    #[test]
+    fn every_coordinate_type_indexes_the_same_cell() {
+        let grid = grid();
+        assert_eq!(grid[(2_i32, 1_i32)], 6);
+        assert_eq!(grid[(2_u32, 1_u32)], 6);
+        assert_eq!(grid[(2_usize, 1_usize)], 6);
+        assert_eq!(grid[IVec2::new(2, 1)], 6);
+        assert_eq!(grid[UVec2::new(2, 1)], 6);
+    }
+
+    #[test]
+    fn index_mut_and_bit_grids_accept_any_coordinate() {
+        let mut grid = grid();
+        grid[(3_u32, 2_u32)] = 100;
+        assert_eq!(grid.get(IVec2::new(3, 2)), Some(&100));
+
+        let mut bits = BitGrid::new((4, 3));
+        assert!(bits.set(UVec2::new(1, 2), true));
+        assert!(bits.is_set((1_usize, 2_usize)));
+    }
+
+    #[test]
+    fn out_of_range_coordinates_are_rejected() {
+        let grid = grid();
+        assert_eq!(grid.get((4_u32, 0_u32)), None);
+        assert_eq!(grid.get(IVec2::new(-1, 0)), None);
+        // Would wrap around to a valid index if the row were not checked
+        assert_eq!(grid.get((0_usize, 3_usize)), None);
+        assert_eq!(grid.get((u32::MAX, 0_u32)), None);
+    }
+
+    #[test]
+    fn bounds_and_index_conversion_accept_any_coordinate() {
+        let grid = grid();
+        assert!(grid.in_bounds(UVec2::new(3, 2)));
+        assert!(!grid.in_bounds(IVec2::new(0, 3)));
+        assert_eq!(grid.position_to_index((2_usize, 1_usize)), Some(6));
+        assert_eq!(grid.position_to_index(IVec2::new(-1, 0)), None);
+        assert_eq!(grid.position_to_index_unchecked(UVec2::new(3, 2)), 11);
+
+        let bits = BitGrid::new((4, 3));
+        assert!(bits.in_bounds(IVec2::new(3, 2)));
+        assert!(!bits.in_bounds((4_u32, 0_u32)));
+        assert_eq!(bits.position_to_index(UVec2::new(2, 1)), Some(6));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid index position")]
+    fn indexing_out_of_range_panics() {
+        let _ = grid()[(0_u32, 3_u32)];
+    }
+}
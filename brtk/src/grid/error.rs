@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum GridParseError {
+    #[error("Grid text is empty")]
+    Empty,
+    #[error("Row {row} has {width} characters, expected {expected}")]
+    RaggedRow { row: usize, width: usize, expected: usize },
+    #[error("Unknown character {character:?} at {position:?}")]
+    UnknownChar { character: char, position: (i32, i32) },
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum GridSizeError {
+    #[error("Grid data has {len} cells, expected {expected}")]
+    DataLength { len: usize, expected: usize },
+    #[error("Chunk has size {size:?}, expected {expected:?}")]
+    ChunkSize { size: (usize, usize), expected: (usize, usize) },
+    #[error("Chunk dimensions must be non-zero")]
+    EmptyChunkSize,
+}
@@ -4,6 +4,8 @@ use std::ops::{Index, IndexMut};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::grid::{circle, circle_outline, line, Connectivity, GridCoord, GridRect};
+
 #[derive(Serialize, Deserialize, Reflect, Debug, Clone)]
 pub struct Grid<T> {
     size: (usize, usize),
@@ -86,7 +88,12 @@ impl<T> Grid<T> {
     /// `is_valid`. Given a `Grid` with size (3, 3), a position (0, 4) is not inside this
     /// `Grid` but provides a valid index.
     #[inline]
-    pub const fn in_bounds(&self, position: (i32, i32)) -> bool {
+    pub fn in_bounds(&self, position: impl GridCoord) -> bool {
+        self.in_bounds_xy(position.grid_position())
+    }
+
+    #[inline]
+    const fn in_bounds_xy(&self, position: (i32, i32)) -> bool {
         position.0 >= 0
             && position.0 < self.width() as i32
             && position.1 >= 0
@@ -107,7 +114,7 @@ impl<T> Grid<T> {
 // Index/Position Conversion
 impl<T> Grid<T> {
     /// Converts a position into an index
-    pub const fn position_to_index(&self, position: (i32, i32)) -> Option<usize> {
+    pub fn position_to_index(&self, position: impl GridCoord) -> Option<usize> {
         if self.in_bounds(position) {
             Some(self.position_to_index_unchecked(position))
         } else {
@@ -117,14 +124,15 @@ impl<T> Grid<T> {
 
     /// Converts a position into an index
     #[inline]
-    pub const fn position_to_index_unchecked(&self, position: (i32, i32)) -> usize {
-        (position.1 * self.width() as i32 + position.0) as usize
+    pub fn position_to_index_unchecked(&self, position: impl GridCoord) -> usize {
+        let (x, y) = position.grid_position();
+        (y * self.width() as i32 + x) as usize
     }
 
     /// Converts an index into a position
     pub const fn index_to_position(&self, index: usize) -> Option<(i32, i32)> {
         let position = self.index_to_position_unchecked(index);
-        if self.in_bounds(position) {
+        if self.in_bounds_xy(position) {
             Some(position)
         } else {
             None
@@ -134,7 +142,7 @@ impl<T> Grid<T> {
     /// Converts an index into a position
     #[inline]
     pub const fn index_to_position_unchecked(&self, index: usize) -> (i32, i32) {
-        ((index % self.width() as usize) as i32, (index / self.width() as usize) as i32)
+        ((index % self.width()) as i32, (index / self.width()) as i32)
     }
 }
 
@@ -165,7 +173,8 @@ impl<T> Grid<T> {
     }
 
     /// Borrow a value at a position
-    pub fn get(&self, position: (i32, i32)) -> Option<&T> {
+    pub fn get(&self, position: impl GridCoord) -> Option<&T> {
+        let position = position.grid_position();
         if self.in_bounds(position) {
             self.get_index(self.position_to_index_unchecked(position))
         } else {
@@ -174,7 +183,8 @@ impl<T> Grid<T> {
     }
 
     /// Mutably borrow a value at a position
-    pub fn get_mut(&mut self, position: (i32, i32)) -> Option<&mut T> {
+    pub fn get_mut(&mut self, position: impl GridCoord) -> Option<&mut T> {
+        let position = position.grid_position();
         if self.in_bounds(position) {
             self.get_mut_index(self.position_to_index_unchecked(position))
         } else {
@@ -185,61 +195,132 @@ impl<T> Grid<T> {
 
 // Iterators
 impl<T> Grid<T> {
-    pub fn iter(&self) -> slice::Iter<T> {
+    pub fn iter(&self) -> slice::Iter<'_, T> {
         self.data.iter()
     }
 
-    pub fn iter_mut(&mut self) -> slice::IterMut<T> {
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
         self.data.iter_mut()
     }
-}
 
-impl<T> Index<usize> for Grid<T> {
-    type Output = T;
+    /// Iterate over every position in row-major order
+    pub fn enumerate_positions(&self) -> impl Iterator<Item = (i32, i32)> {
+        self.bounds().positions()
+    }
 
-    #[inline]
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.data[index]
+    /// Iterate over the position and value of every cell in row-major order
+    pub fn iter_with_positions(&self) -> impl Iterator<Item = ((i32, i32), &T)> {
+        self.data.iter().enumerate().map(|(index, value)| (self.index_to_position_unchecked(index), value))
+    }
+
+    /// Mutably iterate over the position and value of every cell in row-major order
+    pub fn iter_mut_with_positions(&mut self) -> impl Iterator<Item = ((i32, i32), &mut T)> {
+        let width = self.width();
+        self.data
+            .iter_mut()
+            .enumerate()
+            .map(move |(index, value)| (((index % width) as i32, (index / width) as i32), value))
     }
 }
 
-impl<T> IndexMut<usize> for Grid<T> {
-    #[inline]
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.data[index]
+// Spatial Iterators
+//
+// Each of these yields `(position, &value)` pairs and silently skips positions that fall
+// outside of the `Grid`.
+impl<T> Grid<T> {
+    /// Iterate over the in-bounds neighbors of a position
+    pub fn neighbors(
+        &self,
+        position: impl GridCoord,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = ((i32, i32), &T)> {
+        let position = position.grid_position();
+        self.iter_positions(
+            connectivity
+                .offsets()
+                .iter()
+                .map(move |&(dx, dy)| (position.0 + dx, position.1 + dy)),
+        )
+    }
+
+    /// Iterate over the in-bounds cardinal neighbors of a position
+    pub fn neighbors_4(&self, position: impl GridCoord) -> impl Iterator<Item = ((i32, i32), &T)> {
+        self.neighbors(position, Connectivity::Four)
+    }
+
+    /// Iterate over the in-bounds cardinal and diagonal neighbors of a position
+    pub fn neighbors_8(&self, position: impl GridCoord) -> impl Iterator<Item = ((i32, i32), &T)> {
+        self.neighbors(position, Connectivity::Eight)
+    }
+
+    /// Iterate over the cells of a Bresenham line from `from` to `to`, both inclusive
+    pub fn iter_line(
+        &self,
+        from: impl GridCoord,
+        to: impl GridCoord,
+    ) -> impl Iterator<Item = ((i32, i32), &T)> {
+        self.iter_positions(line(from.grid_position(), to.grid_position()))
+    }
+
+    /// Iterate over the cells inside `rect` in row-major order
+    pub fn iter_rect(&self, rect: GridRect) -> impl Iterator<Item = ((i32, i32), &T)> {
+        self.iter_positions(rect.positions())
+    }
+
+    /// Iterate over the cells within `radius` of `center` in row-major order
+    pub fn iter_circle(&self, center: impl GridCoord, radius: i32) -> impl Iterator<Item = ((i32, i32), &T)> {
+        self.iter_positions(circle(center.grid_position(), radius))
+    }
+
+    /// Iterate over the edge cells of [`iter_circle`](Self::iter_circle) in row-major order
+    pub fn iter_circle_outline(
+        &self,
+        center: impl GridCoord,
+        radius: i32,
+    ) -> impl Iterator<Item = ((i32, i32), &T)> {
+        self.iter_positions(circle_outline(center.grid_position(), radius))
+    }
+
+    /// Iterate over the cells at the given positions, skipping any that are out of bounds
+    pub fn iter_positions<'a>(
+        &'a self,
+        positions: impl IntoIterator<Item = impl GridCoord + 'a> + 'a,
+    ) -> impl Iterator<Item = ((i32, i32), &'a T)> + 'a {
+        positions
+            .into_iter()
+            .map(GridCoord::grid_position)
+            .filter_map(move |position| self.get(position).map(|value| (position, value)))
     }
 }
 
-impl<T> Index<(i32, i32)> for Grid<T> {
+impl<T> Index<usize> for Grid<T> {
     type Output = T;
 
     #[inline]
-    fn index(&self, index: (i32, i32)) -> &Self::Output {
-        self.get(index).expect("Invalid index position")
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
     }
 }
 
-impl<T> IndexMut<(i32, i32)> for Grid<T> {
+impl<T> IndexMut<usize> for Grid<T> {
     #[inline]
-    fn index_mut(&mut self, index: (i32, i32)) -> &mut Self::Output {
-        self.get_mut(index).expect("Invalid index position")
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.data[index]
     }
 }
 
-impl<T> Index<(u32, u32)> for Grid<T> {
+impl<T, C: GridCoord> Index<C> for Grid<T> {
     type Output = T;
 
     #[inline]
-    fn index(&self, index: (u32, u32)) -> &Self::Output {
-        let index = (index.0 as i32, index.1 as i32);
+    fn index(&self, index: C) -> &Self::Output {
         self.get(index).expect("Invalid index position")
     }
 }
 
-impl<T> IndexMut<(u32, u32)> for Grid<T> {
+impl<T, C: GridCoord> IndexMut<C> for Grid<T> {
     #[inline]
-    fn index_mut(&mut self, index: (u32, u32)) -> &mut Self::Output {
-        let index = (index.0 as i32, index.1 as i32);
+    fn index_mut(&mut self, index: C) -> &mut Self::Output {
         self.get_mut(index).expect("Invalid index position")
     }
 }
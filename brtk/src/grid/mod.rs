@@ -1,3 +1,22 @@
+mod bit_grid;
+mod chunked_grid;
+mod connectivity;
+mod coord;
+mod error;
 mod grid;
+mod rect;
+mod shapes;
+mod text;
+mod transform;
+mod view;
 
+pub use bit_grid::*;
+pub use chunked_grid::*;
+pub use connectivity::*;
+pub use coord::*;
+pub use error::*;
 pub use grid::*;
+pub use rect::*;
+pub use shapes::*;
+pub use text::*;
+pub use view::*;
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// An axis-aligned rectangle of grid cells, anchored at its top-left corner
+#[derive(Serialize, Deserialize, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl GridRect {
+    /// Create a new `GridRect` from its top-left corner and size
+    pub const fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Create a new `GridRect` spanning two corners, both inclusive
+    pub const fn from_corners(a: (i32, i32), b: (i32, i32)) -> Self {
+        let (min_x, max_x) = if a.0 < b.0 { (a.0, b.0) } else { (b.0, a.0) };
+        let (min_y, max_y) = if a.1 < b.1 { (a.1, b.1) } else { (b.1, a.1) };
+        Self::new(min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)
+    }
+
+    /// The top-left corner, inclusive
+    #[inline]
+    pub const fn min(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    /// The bottom-right corner, exclusive
+    #[inline]
+    pub const fn max(&self) -> (i32, i32) {
+        (self.x + self.width, self.y + self.height)
+    }
+
+    /// The center cell, rounded towards the top-left
+    #[inline]
+    pub const fn center(&self) -> (i32, i32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    /// Number of cells covered by this `GridRect`
+    #[inline]
+    pub const fn area(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else {
+            self.width as usize * self.height as usize
+        }
+    }
+
+    /// Determine if this `GridRect` covers no cells
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    /// Determine if a position is inside this `GridRect`
+    #[inline]
+    pub const fn contains(&self, position: (i32, i32)) -> bool {
+        position.0 >= self.x
+            && position.0 < self.x + self.width
+            && position.1 >= self.y
+            && position.1 < self.y + self.height
+    }
+
+    /// Determine if two rects share at least one cell
+    pub const fn intersects(&self, other: &GridRect) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The cells shared by two rects, which may be empty
+    pub const fn intersection(&self, other: &GridRect) -> GridRect {
+        let min_x = if self.x > other.x { self.x } else { other.x };
+        let min_y = if self.y > other.y { self.y } else { other.y };
+        let max_x = if self.max().0 < other.max().0 { self.max().0 } else { other.max().0 };
+        let max_y = if self.max().1 < other.max().1 { self.max().1 } else { other.max().1 };
+        GridRect::new(min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+
+    /// Grow (or shrink, with a negative amount) this `GridRect` on every side
+    pub const fn expand(&self, amount: i32) -> GridRect {
+        GridRect::new(self.x - amount, self.y - amount, self.width + amount * 2, self.height + amount * 2)
+    }
+
+    /// Iterate over every position inside this `GridRect` in row-major order
+    pub fn positions(&self) -> impl Iterator<Item = (i32, i32)> {
+        let rect = *self;
+        (rect.y..rect.y + rect.height.max(0))
+            .flat_map(move |y| (rect.x..rect.x + rect.width.max(0)).map(move |x| (x, y)))
+    }
+
+    /// Iterate over the positions on the edge of this `GridRect`, each visited once
+    pub fn border_positions(&self) -> impl Iterator<Item = (i32, i32)> {
+        let rect = *self;
+        rect.positions().filter(move |&(x, y)| {
+            x == rect.x || y == rect.y || x == rect.x + rect.width - 1 || y == rect.y + rect.height - 1
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_rects_intersect() {
+        let a = GridRect::new(0, 0, 4, 4);
+        let b = GridRect::new(2, 1, 4, 5);
+        assert!(a.intersects(&b));
+        assert!(b.intersects(&a));
+        assert_eq!(a.intersection(&b), GridRect::new(2, 1, 2, 3));
+        assert_eq!(b.intersection(&a), GridRect::new(2, 1, 2, 3));
+    }
+
+    #[test]
+    fn touching_rects_do_not_intersect() {
+        let a = GridRect::new(0, 0, 4, 4);
+        let b = GridRect::new(4, 0, 2, 4);
+        assert!(!a.intersects(&b));
+        assert!(a.intersection(&b).is_empty());
+        assert_eq!(a.intersection(&b).area(), 0);
+    }
+
+    #[test]
+    fn contained_rect_is_its_own_intersection() {
+        let outer = GridRect::new(-2, -2, 10, 10);
+        let inner = GridRect::new(1, 1, 3, 2);
+        assert_eq!(outer.intersection(&inner), inner);
+    }
+
+    #[test]
+    fn expand_grows_and_shrinks_every_side() {
+        let rect = GridRect::new(2, 3, 4, 5);
+        let grown = rect.expand(1);
+        assert_eq!(grown, GridRect::new(1, 2, 6, 7));
+        assert_eq!(grown.center(), rect.center());
+        assert_eq!(grown.expand(-1), rect);
+        assert!(rect.expand(-3).is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::grid::GridRect;
+
+/// Iterate over the cells of a straight line from `from` to `to`, both inclusive.
+///
+/// Uses Bresenham's algorithm, so consecutive cells are always 8-connected.
+pub fn line(from: (i32, i32), to: (i32, i32)) -> Line {
+    let dx = (to.0 - from.0).abs();
+    let dy = -(to.1 - from.1).abs();
+    Line {
+        current: from,
+        end: to,
+        dx,
+        dy,
+        step: ((to.0 - from.0).signum(), (to.1 - from.1).signum()),
+        error: dx + dy,
+        done: false,
+    }
+}
+
+/// Iterate over the cells within `radius` of `center`, in row-major order.
+///
+/// A cell belongs to the circle when its squared distance to the center is at most
+/// `radius * radius`. A negative radius yields nothing.
+pub fn circle(center: (i32, i32), radius: i32) -> impl Iterator<Item = (i32, i32)> {
+    GridRect::new(center.0 - radius, center.1 - radius, radius * 2 + 1, radius * 2 + 1)
+        .positions()
+        .filter(move |&position| in_circle(center, radius, position))
+}
+
+/// Iterate over the edge cells of [`circle`], in row-major order.
+///
+/// An edge cell is inside the circle and has at least one cardinal neighbor outside of it, so
+/// the outline is 4-connected and has no gaps.
+pub fn circle_outline(center: (i32, i32), radius: i32) -> impl Iterator<Item = (i32, i32)> {
+    circle(center, radius).filter(move |&(x, y)| {
+        [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .iter()
+            .any(|&(dx, dy)| !in_circle(center, radius, (x + dx, y + dy)))
+    })
+}
+
+#[inline]
+const fn in_circle(center: (i32, i32), radius: i32, position: (i32, i32)) -> bool {
+    let dx = position.0 - center.0;
+    let dy = position.1 - center.1;
+    radius >= 0 && dx * dx + dy * dy <= radius * radius
+}
+
+/// Iterator over the cells of a Bresenham line, created with [`line`]
+#[derive(Debug, Clone)]
+pub struct Line {
+    current: (i32, i32),
+    end: (i32, i32),
+    dx: i32,
+    dy: i32,
+    step: (i32, i32),
+    error: i32,
+    done: bool,
+}
+
+impl Iterator for Line {
+    type Item = (i32, i32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let position = self.current;
+        if position == self.end {
+            self.done = true;
+            return Some(position);
+        }
+
+        let doubled_error = self.error * 2;
+        if doubled_error >= self.dy {
+            self.error += self.dy;
+            self.current.0 += self.step.0;
+        }
+        if doubled_error <= self.dx {
+            self.error += self.dx;
+            self.current.1 += self.step.1;
+        }
+
+        Some(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_8_connected(cells: &[(i32, i32)]) -> bool {
+        cells.windows(2).all(|pair| {
+            let (dx, dy) = (pair[1].0 - pair[0].0, pair[1].1 - pair[0].1);
+            dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0)
+        })
+    }
+
+    #[test]
+    fn line_includes_both_endpoints() {
+        let cells: Vec<_> = line((1, 2), (7, 5)).collect();
+        assert_eq!(cells.first(), Some(&(1, 2)));
+        assert_eq!(cells.last(), Some(&(7, 5)));
+        assert_eq!(cells.len(), 7);
+        assert!(is_8_connected(&cells));
+    }
+
+    #[test]
+    fn line_is_connected_in_every_direction() {
+        for to in [(5, 0), (-5, 0), (0, 5), (0, -5), (4, 4), (-4, -4), (-6, 3), (2, -7)] {
+            let cells: Vec<_> = line((0, 0), to).collect();
+            assert_eq!(cells.last(), Some(&to));
+            assert!(is_8_connected(&cells), "line to {to:?} has a gap");
+        }
+    }
+
+    #[test]
+    fn line_to_itself_is_one_cell() {
+        assert_eq!(line((3, 3), (3, 3)).collect::<Vec<_>>(), vec![(3, 3)]);
+    }
+
+    #[test]
+    fn circle_covers_cells_within_radius() {
+        let cells: Vec<_> = circle((0, 0), 1).collect();
+        assert_eq!(cells, vec![(0, -1), (-1, 0), (0, 0), (1, 0), (0, 1)]);
+        assert_eq!(circle((4, 4), 0).collect::<Vec<_>>(), vec![(4, 4)]);
+        assert_eq!(circle((4, 4), -1).count(), 0);
+        assert!(circle((2, -3), 3).all(|(x, y)| (x - 2).pow(2) + (y + 3).pow(2) <= 9));
+    }
+
+    #[test]
+    fn circle_outline_is_the_edge_of_the_circle() {
+        let filled: Vec<_> = circle((0, 0), 4).collect();
+        let outline: Vec<_> = circle_outline((0, 0), 4).collect();
+        assert!(outline.iter().all(|cell| filled.contains(cell)));
+        assert!(!outline.contains(&(0, 0)));
+        for cell in [(4, 0), (-4, 0), (0, 4), (0, -4)] {
+            assert!(outline.contains(&cell));
+        }
+        // Interior cells are fully surrounded by the circle
+        assert!(filled
+            .iter()
+            .filter(|cell| !outline.contains(cell))
+            .all(|&(x, y)| in_circle((0, 0), 4, (x + 1, y)) && in_circle((0, 0), 4, (x - 1, y))));
+    }
+}
@@ -0,0 +1,144 @@
+use std::{cell::RefCell, fmt};
+
+use crate::grid::{Grid, GridParseError};
+
+// Text
+//
+// Text is read and written one row per line, with the first line holding `y == 0`.
+impl<T> Grid<T> {
+    /// Parse a `Grid` from multi-line text, mapping each character to a value with `f`.
+    ///
+    /// Leading and trailing blank lines are ignored so fixtures can be written as raw string
+    /// literals. Every remaining line must have the same number of characters, and `f` returning
+    /// `None` is reported as an unknown character.
+    pub fn from_text(text: &str, mut f: impl FnMut(char) -> Option<T>) -> Result<Self, GridParseError> {
+        let lines: Vec<&str> = text.lines().map(|line| line.trim_end_matches('\r')).collect();
+        let first = lines.iter().position(|line| !line.trim().is_empty()).ok_or(GridParseError::Empty)?;
+        let last = lines.iter().rposition(|line| !line.trim().is_empty()).unwrap_or(first);
+        let lines = &lines[first..=last];
+
+        let width = lines[0].chars().count();
+        let mut data = Vec::with_capacity(width * lines.len());
+
+        for (y, line) in lines.iter().enumerate() {
+            let row_width = line.chars().count();
+            if row_width != width {
+                return Err(GridParseError::RaggedRow { row: y, width: row_width, expected: width });
+            }
+
+            for (x, character) in line.chars().enumerate() {
+                let value = f(character)
+                    .ok_or(GridParseError::UnknownChar { character, position: (x as i32, y as i32) })?;
+                data.push(value);
+            }
+        }
+
+        Ok(Self::new((width, lines.len()), data))
+    }
+
+    /// Render this `Grid` as multi-line text, mapping each value to a character with `f`.
+    ///
+    /// Rows are separated by `\n`, without a trailing newline.
+    pub fn to_text(&self, f: impl FnMut(&T) -> char) -> String {
+        self.display_with(f).to_string()
+    }
+
+    /// Borrow this `Grid` as something that can be formatted with `{}`, mapping each value to a
+    /// character with `f`. Useful for logging without building an intermediate `String`.
+    pub fn display_with<F>(&self, f: F) -> GridDisplay<'_, T, F>
+    where
+        F: FnMut(&T) -> char,
+    {
+        GridDisplay { grid: self, f: RefCell::new(f) }
+    }
+}
+
+/// Formats a `Grid` one row per line using a `T -> char` mapping, created with
+/// [`Grid::display_with`]
+pub struct GridDisplay<'a, T, F> {
+    grid: &'a Grid<T>,
+    f: RefCell<F>,
+}
+
+impl<T, F> fmt::Display for GridDisplay<'_, T, F>
+where
+    F: FnMut(&T) -> char,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = self.f.borrow_mut();
+        write_rows(self.grid, f, |f, value| write!(f, "{}", map(value)))
+    }
+}
+
+/// Formats every cell with its own `Display`, one row per line
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_rows(self, f, |f, value| write!(f, "{value}"))
+    }
+}
+
+fn write_rows<T>(
+    grid: &Grid<T>,
+    f: &mut fmt::Formatter<'_>,
+    mut write_cell: impl FnMut(&mut fmt::Formatter<'_>, &T) -> fmt::Result,
+) -> fmt::Result {
+    for (index, value) in grid.iter().enumerate() {
+        if index > 0 && index % grid.width() == 0 {
+            writeln!(f)?;
+        }
+        write_cell(f, value)?;
+    }
+
+    Ok(())
+}
+
+// Test fixtures, drawn with one character per cell
+#[cfg(test)]
+impl Grid<char> {
+    pub(crate) fn from_chars(text: &str) -> Self {
+        Self::from_text(text, Some).expect("Test fixtures should parse")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Grid<bool>, GridParseError> {
+        Grid::from_text(text, |character| match character {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn round_trips_through_text() {
+        let grid = parse("\n##.\n.#.\n").unwrap();
+        assert_eq!(grid.size(), (3, 2));
+        assert!(grid[(0, 0)] && !grid[(0, 1)]);
+        assert_eq!(grid.to_text(|&wall| if wall { '#' } else { '.' }), "##.\n.#.");
+    }
+
+    #[test]
+    fn empty_text_is_an_error() {
+        assert_eq!(parse("").unwrap_err(), GridParseError::Empty);
+        assert_eq!(parse("\n  \n").unwrap_err(), GridParseError::Empty);
+    }
+
+    #[test]
+    fn ragged_rows_are_an_error() {
+        assert_eq!(
+            parse("###\n##\n###").unwrap_err(),
+            GridParseError::RaggedRow { row: 1, width: 2, expected: 3 }
+        );
+    }
+
+    #[test]
+    fn unknown_glyphs_are_an_error() {
+        assert_eq!(
+            parse("###\n#x#").unwrap_err(),
+            GridParseError::UnknownChar { character: 'x', position: (1, 1) }
+        );
+    }
+}
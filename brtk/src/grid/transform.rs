@@ -0,0 +1,138 @@
+use crate::grid::Grid;
+
+// Transforms
+//
+// All transforms treat the grid as row-major with `(0, 0)` in the first row, so "clockwise" is
+// as seen when the rows are printed top to bottom.
+impl<T> Grid<T> {
+    /// Create a new `Grid` by applying `f` to every value
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.size(), self.iter().map(f).collect())
+    }
+
+    /// Create a new `Grid` by applying `f` to the position and value of every cell
+    pub fn map_with_positions<U>(&self, mut f: impl FnMut((i32, i32), &T) -> U) -> Grid<U> {
+        Grid::new_fn(self.size(), |index, (x, y)| f((x as i32, y as i32), &self[index]))
+    }
+
+    /// Create a new `Grid` by combining the values of two grids cell by cell.
+    /// Panics if the grids differ in size.
+    pub fn zip_with<U, V>(&self, other: &Grid<U>, mut f: impl FnMut(&T, &U) -> V) -> Grid<V> {
+        assert_eq!(self.size(), other.size(), "Grids must have the same dimensions");
+        Grid::new(self.size(), self.iter().zip(other.iter()).map(|(a, b)| f(a, b)).collect())
+    }
+
+    /// Create a new `Grid` rotated a quarter turn clockwise; width and height are swapped
+    pub fn rotate_90(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let height = self.height() as i32;
+        self.remap((self.height(), self.width()), |(x, y)| (y, height - 1 - x))
+    }
+
+    /// Create a new `Grid` rotated a half turn
+    pub fn rotate_180(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let (width, height) = (self.width() as i32, self.height() as i32);
+        self.remap(self.size(), |(x, y)| (width - 1 - x, height - 1 - y))
+    }
+
+    /// Create a new `Grid` rotated a quarter turn counter-clockwise; width and height are swapped
+    pub fn rotate_270(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let width = self.width() as i32;
+        self.remap((self.height(), self.width()), |(x, y)| (width - 1 - y, x))
+    }
+
+    /// Create a new `Grid` mirrored left to right
+    pub fn flip_horizontal(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let width = self.width() as i32;
+        self.remap(self.size(), |(x, y)| (width - 1 - x, y))
+    }
+
+    /// Create a new `Grid` mirrored top to bottom
+    pub fn flip_vertical(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let height = self.height() as i32;
+        self.remap(self.size(), |(x, y)| (x, height - 1 - y))
+    }
+
+    /// Create a new `Grid` mirrored along its main diagonal; width and height are swapped
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.remap((self.height(), self.width()), |(x, y)| (y, x))
+    }
+
+    /// Build a grid of `size` where each cell copies the cell of `self` at `source(position)`
+    fn remap(&self, size: (usize, usize), source: impl Fn((i32, i32)) -> (i32, i32)) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::new_fn(size, |_index, (x, y)| self[source((x as i32, y as i32))].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Non-square, so a transform that mixes up width and height shows up
+    fn grid() -> Grid<char> {
+        Grid::from_text("abc\ndef", Some).unwrap()
+    }
+
+    fn text(grid: &Grid<char>) -> String {
+        grid.to_text(|&character| character)
+    }
+
+    #[test]
+    fn rotations_turn_clockwise() {
+        assert_eq!(text(&grid().rotate_90()), "da\neb\nfc");
+        assert_eq!(text(&grid().rotate_180()), "fed\ncba");
+        assert_eq!(text(&grid().rotate_270()), "cf\nbe\nad");
+        assert_eq!(text(&grid().transpose()), "ad\nbe\ncf");
+    }
+
+    #[test]
+    fn four_quarter_turns_are_the_identity() {
+        let rotated = grid().rotate_90().rotate_90().rotate_90().rotate_90();
+        assert_eq!(rotated.size(), (3, 2));
+        assert_eq!(text(&rotated), text(&grid()));
+        assert_eq!(text(&grid().rotate_90().rotate_90()), text(&grid().rotate_180()));
+    }
+
+    #[test]
+    fn opposite_rotations_cancel_out() {
+        assert_eq!(text(&grid().rotate_90().rotate_270()), text(&grid()));
+        assert_eq!(text(&grid().rotate_270().rotate_90()), text(&grid()));
+    }
+
+    #[test]
+    fn flipping_twice_is_the_identity() {
+        assert_eq!(text(&grid().flip_horizontal()), "cba\nfed");
+        assert_eq!(text(&grid().flip_vertical()), "def\nabc");
+        assert_eq!(text(&grid().flip_horizontal().flip_horizontal()), text(&grid()));
+        assert_eq!(text(&grid().flip_vertical().flip_vertical()), text(&grid()));
+        assert_eq!(text(&grid().transpose().transpose()), text(&grid()));
+    }
+
+    #[test]
+    fn map_and_zip_with_keep_positions() {
+        let codes = grid().map(|&character| character as u32);
+        let offsets = grid().map_with_positions(|(x, y), _| (x + y * 3) as u32);
+        let zipped = codes.zip_with(&offsets, |code, offset| char::from_u32(code - offset).unwrap());
+        assert_eq!(text(&zipped), "aaa\naaa");
+    }
+}
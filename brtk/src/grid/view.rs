@@ -0,0 +1,283 @@
+use crate::grid::{Grid, GridRect};
+
+/// A borrowed, read-only window onto a rectangle of a `Grid`.
+///
+/// Positions passed to a `GridView` are local to the window, so `(0, 0)` is the top-left
+/// corner of its rect.
+#[derive(Debug, Clone, Copy)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    rect: GridRect,
+}
+
+/// A borrowed, writable window onto a rectangle of a `Grid`.
+///
+/// Positions passed to a `GridViewMut` are local to the window, so `(0, 0)` is the top-left
+/// corner of its rect.
+#[derive(Debug)]
+pub struct GridViewMut<'a, T> {
+    grid: &'a mut Grid<T>,
+    rect: GridRect,
+}
+
+impl<T> Grid<T> {
+    /// The rect covering every cell of this `Grid`
+    #[inline]
+    pub const fn bounds(&self) -> GridRect {
+        GridRect::new(0, 0, self.width() as i32, self.height() as i32)
+    }
+
+    /// Borrow a window onto `rect`, or `None` if `rect` is not entirely inside this `Grid`
+    pub fn view(&self, rect: GridRect) -> Option<GridView<'_, T>> {
+        self.contains_rect(rect).then_some(GridView { grid: self, rect })
+    }
+
+    /// Mutably borrow a window onto `rect`, or `None` if `rect` is not entirely inside this `Grid`
+    pub fn view_mut(&mut self, rect: GridRect) -> Option<GridViewMut<'_, T>> {
+        if self.contains_rect(rect) {
+            Some(GridViewMut { grid: self, rect })
+        } else {
+            None
+        }
+    }
+
+    /// Copy the cells under `rect` into a new `Grid`, or `None` if `rect` is not entirely inside
+    /// this `Grid`
+    pub fn crop(&self, rect: GridRect) -> Option<Grid<T>>
+    where
+        T: Clone,
+    {
+        self.view(rect).map(|view| view.to_grid())
+    }
+
+    /// Copy the cells of `source` into this `Grid` with its top-left corner at `offset`.
+    ///
+    /// Only cells for which `mask` returns true are copied; `mask` receives the position local to
+    /// `source`. Cells that would land outside of this `Grid` are skipped.
+    pub fn blit(
+        &mut self,
+        source: &Grid<T>,
+        offset: (i32, i32),
+        mut mask: impl FnMut((i32, i32), &T) -> bool,
+    ) where
+        T: Clone,
+    {
+        for (index, value) in source.iter().enumerate() {
+            let local = source.index_to_position_unchecked(index);
+            if !mask(local, value) {
+                continue;
+            }
+
+            if let Some(cell) = self.get_mut((local.0 + offset.0, local.1 + offset.1)) {
+                *cell = value.clone();
+            }
+        }
+    }
+
+    /// Change the size of this `Grid`, keeping existing cells anchored to the top-left corner
+    /// and filling new cells with `value`
+    pub fn resize(&mut self, size: (usize, usize), value: T)
+    where
+        T: Clone,
+    {
+        let (old_width, old_height) = self.size();
+        let mut old = std::mem::take(self.data_mut()).into_iter();
+        let mut data = Vec::with_capacity(size.0 * size.1);
+
+        for y in 0..size.1 {
+            let mut row = old.by_ref().take(if y < old_height { old_width } else { 0 });
+            for _ in 0..size.0 {
+                data.push(row.next().unwrap_or_else(|| value.clone()));
+            }
+
+            // Drop whatever is left of a row that got narrower
+            row.for_each(drop);
+        }
+
+        *self = Grid::new(size, data);
+    }
+
+    fn contains_rect(&self, rect: GridRect) -> bool {
+        rect.x >= 0
+            && rect.y >= 0
+            && rect.width >= 0
+            && rect.height >= 0
+            && rect.x + rect.width <= self.width() as i32
+            && rect.y + rect.height <= self.height() as i32
+    }
+}
+
+impl<'a, T> GridView<'a, T> {
+    /// The rect of the underlying `Grid` covered by this view
+    #[inline]
+    pub const fn rect(&self) -> GridRect {
+        self.rect
+    }
+
+    /// Obtain the size of this view
+    #[inline]
+    pub const fn size(&self) -> (usize, usize) {
+        (self.rect.width as usize, self.rect.height as usize)
+    }
+
+    /// Determine if a local position is inside of this view
+    #[inline]
+    pub const fn in_bounds(&self, position: (i32, i32)) -> bool {
+        position.0 >= 0 && position.0 < self.rect.width && position.1 >= 0 && position.1 < self.rect.height
+    }
+
+    /// Converts a local position into a position of the underlying `Grid`
+    #[inline]
+    pub const fn to_grid_position(&self, position: (i32, i32)) -> (i32, i32) {
+        (position.0 + self.rect.x, position.1 + self.rect.y)
+    }
+
+    /// Borrow a value at a local position
+    pub fn get(&self, position: (i32, i32)) -> Option<&'a T> {
+        if self.in_bounds(position) {
+            self.grid.get(self.to_grid_position(position))
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over the local position and value of every cell in row-major order
+    pub fn iter_with_positions(&self) -> impl Iterator<Item = ((i32, i32), &'a T)> + '_ {
+        let grid = self.grid;
+        GridRect::new(0, 0, self.rect.width, self.rect.height)
+            .positions()
+            .map(move |position| (position, &grid[self.to_grid_position(position)]))
+    }
+
+    /// Copy the cells of this view into a new `Grid`
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::new_fn(self.size(), |_index, (x, y)| {
+            self.grid[self.to_grid_position((x as i32, y as i32))].clone()
+        })
+    }
+}
+
+impl<T> GridViewMut<'_, T> {
+    /// The rect of the underlying `Grid` covered by this view
+    #[inline]
+    pub const fn rect(&self) -> GridRect {
+        self.rect
+    }
+
+    /// Obtain the size of this view
+    #[inline]
+    pub const fn size(&self) -> (usize, usize) {
+        (self.rect.width as usize, self.rect.height as usize)
+    }
+
+    /// Determine if a local position is inside of this view
+    #[inline]
+    pub const fn in_bounds(&self, position: (i32, i32)) -> bool {
+        position.0 >= 0 && position.0 < self.rect.width && position.1 >= 0 && position.1 < self.rect.height
+    }
+
+    /// Converts a local position into a position of the underlying `Grid`
+    #[inline]
+    pub const fn to_grid_position(&self, position: (i32, i32)) -> (i32, i32) {
+        (position.0 + self.rect.x, position.1 + self.rect.y)
+    }
+
+    /// Reborrow this window as read-only
+    pub fn as_view(&self) -> GridView<'_, T> {
+        GridView { grid: self.grid, rect: self.rect }
+    }
+
+    /// Borrow a value at a local position
+    pub fn get(&self, position: (i32, i32)) -> Option<&T> {
+        if self.in_bounds(position) {
+            self.grid.get(self.to_grid_position(position))
+        } else {
+            None
+        }
+    }
+
+    /// Mutably borrow a value at a local position
+    pub fn get_mut(&mut self, position: (i32, i32)) -> Option<&mut T> {
+        if self.in_bounds(position) {
+            let position = self.to_grid_position(position);
+            self.grid.get_mut(position)
+        } else {
+            None
+        }
+    }
+
+    /// Set every cell of this view to `value`
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for position in self.rect.positions() {
+            self.grid[position] = value.clone();
+        }
+    }
+
+    /// Apply `f` to the local position and value of every cell in row-major order
+    pub fn for_each_mut(&mut self, mut f: impl FnMut((i32, i32), &mut T)) {
+        for position in self.rect.positions() {
+            let local = (position.0 - self.rect.x, position.1 - self.rect.y);
+            f(local, &mut self.grid[position]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(grid: &Grid<char>) -> String {
+        grid.to_text(|&character| character)
+    }
+
+    #[test]
+    fn crop_inside_the_grid() {
+        let grid = Grid::from_chars("abcd\nefgh\nijkl");
+        let cropped = grid.crop(GridRect::new(1, 1, 2, 2)).unwrap();
+        assert_eq!(text(&cropped), "fg\njk");
+
+        let view = grid.view(GridRect::new(2, 0, 2, 3)).unwrap();
+        assert_eq!(view.get((0, 0)), Some(&'c'));
+        assert_eq!(view.get((2, 0)), None);
+    }
+
+    #[test]
+    fn crop_partly_outside_the_grid_is_none() {
+        let grid = Grid::from_chars("abcd\nefgh\nijkl");
+        assert!(grid.crop(GridRect::new(3, 1, 2, 2)).is_none());
+        assert!(grid.crop(GridRect::new(-1, 0, 2, 2)).is_none());
+        assert!(grid.crop(GridRect::new(0, 2, 4, 2)).is_none());
+        assert!(grid.crop(grid.bounds()).is_some());
+    }
+
+    #[test]
+    fn blit_clips_to_the_grid_and_honors_the_mask() {
+        let mut target = Grid::from_chars("....\n....\n....");
+        let source = Grid::from_chars("ab\nc ");
+
+        target.blit(&source, (3, 1), |_, _| true);
+        assert_eq!(text(&target), "....\n...a\n...c");
+
+        target.blit(&source, (-1, -1), |_, &character| character != ' ');
+        assert_eq!(text(&target), "....\n...a\n...c");
+
+        target.blit(&source, (0, 0), |_, &character| character != ' ');
+        assert_eq!(text(&target), "ab..\nc..a\n...c");
+    }
+
+    #[test]
+    fn resize_keeps_the_top_left_and_fills_new_cells() {
+        let mut grid = Grid::from_chars("ab\ncd");
+        grid.resize((3, 3), '.');
+        assert_eq!(text(&grid), "ab.\ncd.\n...");
+
+        grid.resize((1, 2), '#');
+        assert_eq!(text(&grid), "a\nc");
+    }
+}
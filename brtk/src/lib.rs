@@ -1,5 +1,7 @@
 pub mod grid;
+pub mod pathfinding;
+pub mod region;
 
 pub mod prelude {
-    pub use crate::grid::*;
+    pub use crate::{grid::*, pathfinding::*, region::*};
 }
@@ -0,0 +1,186 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    grid::{Connectivity, Grid, GridCoord},
+    pathfinding::PathError,
+};
+
+/// Find the cheapest path between two positions of a `Grid` using A*.
+///
+/// `cost` is called with a position and its value and returns the cost of stepping onto that
+/// cell, or `None` if the cell is impassable. Costs below 1 are treated as 1 so the distance
+/// heuristic stays admissible. Diagonal steps (with `Connectivity::Eight`) cost the same as
+/// cardinal steps.
+///
+/// The returned path excludes `start` and ends with `goal`, so the first element is the next
+/// step to take. It is empty when `start == goal`.
+pub fn astar<T>(
+    grid: &Grid<T>,
+    start: impl GridCoord,
+    goal: impl GridCoord,
+    connectivity: Connectivity,
+    mut cost: impl FnMut((i32, i32), &T) -> Option<u32>,
+) -> Result<Vec<(i32, i32)>, PathError> {
+    let (start, goal) = (start.grid_position(), goal.grid_position());
+    let Some(start_index) = grid.position_to_index(start) else {
+        return Err(PathError::OutOfBounds(start));
+    };
+    let Some(goal_index) = grid.position_to_index(goal) else {
+        return Err(PathError::OutOfBounds(goal));
+    };
+
+    if start_index == goal_index {
+        return Ok(Vec::new());
+    }
+
+    let capacity = grid.data().len();
+    let mut g_scores = vec![u32::MAX; capacity];
+    let mut came_from = vec![usize::MAX; capacity];
+
+    // Ordered by (f score, h score) so ties prefer nodes closer to the goal
+    let mut open = BinaryHeap::new();
+    g_scores[start_index] = 0;
+    open.push(Reverse((connectivity.distance(start, goal), connectivity.distance(start, goal), start_index)));
+
+    while let Some(Reverse((f_score, h_score, index))) = open.pop() {
+        if index == goal_index {
+            return Ok(reconstruct_path(grid, &came_from, start_index, goal_index));
+        }
+
+        let g_score = g_scores[index];
+
+        // Skip stale heap entries that were superseded by a cheaper route
+        if f_score - h_score > g_score {
+            continue;
+        }
+
+        let position = grid.index_to_position_unchecked(index);
+        for &(dx, dy) in connectivity.offsets() {
+            let next = (position.0 + dx, position.1 + dy);
+            let Some(next_index) = grid.position_to_index(next) else {
+                continue;
+            };
+
+            let Some(step_cost) = cost(next, &grid[next_index]) else {
+                continue;
+            };
+
+            let tentative = g_score.saturating_add(step_cost.max(1));
+            if tentative < g_scores[next_index] {
+                g_scores[next_index] = tentative;
+                came_from[next_index] = index;
+
+                let h = connectivity.distance(next, goal);
+                open.push(Reverse((tentative.saturating_add(h), h, next_index)));
+            }
+        }
+    }
+
+    Err(PathError::NoPath { start, goal })
+}
+
+/// Find the shortest path between two positions of a `Grid` using A*.
+///
+/// Every cell for which `passable` returns true costs the same to enter. See [`astar`] for the
+/// shape of the returned path.
+pub fn find_path<T>(
+    grid: &Grid<T>,
+    start: impl GridCoord,
+    goal: impl GridCoord,
+    connectivity: Connectivity,
+    mut passable: impl FnMut((i32, i32), &T) -> bool,
+) -> Result<Vec<(i32, i32)>, PathError> {
+    astar(grid, start, goal, connectivity, |position, value| passable(position, value).then_some(1))
+}
+
+/// Walk `came_from` back from the goal and return the path in travel order, excluding the start
+fn reconstruct_path<T>(
+    grid: &Grid<T>,
+    came_from: &[usize],
+    start_index: usize,
+    goal_index: usize,
+) -> Vec<(i32, i32)> {
+    let mut path = Vec::new();
+    let mut current = goal_index;
+
+    while current != start_index {
+        path.push(grid.index_to_position_unchecked(current));
+        current = came_from[current];
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(_: (i32, i32), value: &char) -> bool {
+        *value != '#'
+    }
+
+    #[test]
+    fn straight_path() {
+        let grid = Grid::from_chars(".....\n.....\n.....");
+        let path = find_path(&grid, (0, 1), (4, 1), Connectivity::Four, open).unwrap();
+
+        assert_eq!(path, vec![(1, 1), (2, 1), (3, 1), (4, 1)]);
+        assert_eq!(find_path(&grid, (2, 2), (2, 2), Connectivity::Four, open), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn path_goes_around_obstacles() {
+        let grid = Grid::from_chars(
+            "
+.....
+.###.
+.....
+",
+        );
+        let path = find_path(&grid, (0, 1), (4, 1), Connectivity::Four, open).unwrap();
+
+        assert_eq!(path.len(), 6);
+        assert_eq!(path.last(), Some(&(4, 1)));
+        assert!(path.iter().all(|&position| open(position, &grid[position])));
+    }
+
+    #[test]
+    fn eight_connected_paths_take_diagonals() {
+        let grid = Grid::from_chars("....\n....\n....\n....");
+
+        let four = find_path(&grid, (0, 0), (3, 3), Connectivity::Four, open).unwrap();
+        let eight = find_path(&grid, (0, 0), (3, 3), Connectivity::Eight, open).unwrap();
+
+        assert_eq!(four.len(), 6);
+        assert_eq!(eight, vec![(1, 1), (2, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn out_of_bounds_start_or_goal_is_an_error() {
+        let grid = Grid::from_chars("...\n...");
+
+        let start_outside = find_path(&grid, (-1, 0), (2, 1), Connectivity::Four, open);
+        let goal_outside = find_path(&grid, (0, 0), (3, 1), Connectivity::Four, open);
+
+        assert_eq!(start_outside, Err(PathError::OutOfBounds((-1, 0))));
+        assert_eq!(goal_outside, Err(PathError::OutOfBounds((3, 1))));
+    }
+
+    #[test]
+    fn enclosed_goal_has_no_path() {
+        let grid = Grid::from_chars(
+            "
+.....
+.###.
+.#.#.
+.###.
+",
+        );
+
+        assert_eq!(
+            find_path(&grid, (0, 0), (2, 2), Connectivity::Eight, open),
+            Err(PathError::NoPath { start: (0, 0), goal: (2, 2) })
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::grid::{Connectivity, Grid, GridCoord};
+
+/// A distance field over a `Grid`, where every passable cell holds its distance to the nearest
+/// goal.
+///
+/// This is the classic roguelike "Dijkstra map": an actor rolls [`downhill`](Self::downhill) to
+/// approach the goals, or uses an inverted and rescanned [`flee_map`](Self::flee_map) to run from
+/// them. Maps of the same size can be blended with [`add_weighted`](Self::add_weighted).
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone)]
+pub struct DijkstraMap {
+    connectivity: Connectivity,
+    distances: Grid<f32>,
+    passable: Grid<bool>,
+}
+
+// Constructors
+impl DijkstraMap {
+    /// Value stored in cells that no goal can reach, including impassable cells
+    pub const UNREACHABLE: f32 = f32::INFINITY;
+
+    /// Build a distance field over `grid` from every position in `goals` at once.
+    ///
+    /// `passable` decides which cells may be crossed; goals that are out of bounds or impassable
+    /// are ignored. Every step costs 1, including diagonal steps with `Connectivity::Eight`.
+    pub fn new<T>(
+        grid: &Grid<T>,
+        goals: impl IntoIterator<Item = impl GridCoord>,
+        connectivity: Connectivity,
+        passable: impl FnMut((i32, i32), &T) -> bool,
+    ) -> Self {
+        Self::new_weighted(grid, goals.into_iter().map(|goal| (goal, 0.0)), connectivity, passable)
+    }
+
+    /// Build a distance field over `grid` from goals that each start at their own value.
+    ///
+    /// Lower starting values make a goal more attractive, which lets some goals (treasure, the
+    /// player) pull harder than others.
+    pub fn new_weighted<T>(
+        grid: &Grid<T>,
+        goals: impl IntoIterator<Item = (impl GridCoord, f32)>,
+        connectivity: Connectivity,
+        mut passable: impl FnMut((i32, i32), &T) -> bool,
+    ) -> Self {
+        let passable =
+            Grid::new_fn(grid.size(), |index, (x, y)| passable((x as i32, y as i32), &grid[index]));
+        let mut distances = Grid::new_fill(grid.size(), Self::UNREACHABLE);
+
+        for (goal, value) in goals {
+            if passable.get(goal).copied().unwrap_or(false) {
+                let cell = &mut distances[goal];
+                *cell = cell.min(value);
+            }
+        }
+
+        let mut map = Self { connectivity, distances, passable };
+        map.rescan();
+        map
+    }
+}
+
+impl DijkstraMap {
+    /// Obtain the size of this `DijkstraMap`
+    #[inline]
+    pub const fn size(&self) -> (usize, usize) {
+        self.distances.size()
+    }
+
+    /// Obtain the connectivity used to relax this `DijkstraMap`
+    #[inline]
+    pub const fn connectivity(&self) -> Connectivity {
+        self.connectivity
+    }
+
+    /// Borrow the raw distance field
+    #[inline]
+    pub const fn distances(&self) -> &Grid<f32> {
+        &self.distances
+    }
+
+    /// Obtain the distance at a position, or `None` if it is out of bounds or unreachable
+    pub fn get(&self, position: impl GridCoord) -> Option<f32> {
+        self.distances.get(position).copied().filter(|distance| distance.is_finite())
+    }
+
+    /// Determine if a position can be reached from any goal
+    pub fn is_reachable(&self, position: impl GridCoord) -> bool {
+        self.get(position).is_some()
+    }
+
+    /// Round the distance field into whole steps, with `u32::MAX` marking unreachable cells
+    ///
+    /// Negative values (as found in flee maps) saturate to 0.
+    pub fn to_steps(&self) -> Grid<u32> {
+        Grid::new_fn(self.size(), |index, _| {
+            let distance = self.distances[index];
+            if distance.is_finite() {
+                distance.round().max(0.0) as u32
+            } else {
+                u32::MAX
+            }
+        })
+    }
+}
+
+// Transformations
+impl DijkstraMap {
+    /// Relax the distance field until every cell is at most one step above its lowest neighbor.
+    ///
+    /// Call this after editing values by hand, for example after [`scale`](Self::scale).
+    pub fn rescan(&mut self) {
+        let mut open: BinaryHeap<Node> = self
+            .distances
+            .iter()
+            .enumerate()
+            .filter(|(_, distance)| distance.is_finite())
+            .map(|(index, &distance)| Node { distance, index })
+            .collect();
+
+        while let Some(Node { distance, index }) = open.pop() {
+            // Skip stale heap entries that were superseded by a lower value
+            if distance > self.distances[index] {
+                continue;
+            }
+
+            let position = self.distances.index_to_position_unchecked(index);
+            for &(dx, dy) in self.connectivity.offsets() {
+                let next = (position.0 + dx, position.1 + dy);
+                let Some(next_index) = self.distances.position_to_index(next) else {
+                    continue;
+                };
+
+                if !self.passable[next_index] {
+                    continue;
+                }
+
+                let tentative = distance + 1.0;
+                if tentative < self.distances[next_index] {
+                    self.distances[next_index] = tentative;
+                    open.push(Node { distance: tentative, index: next_index });
+                }
+            }
+        }
+    }
+
+    /// Multiply every reachable distance by `factor`, without rescanning
+    pub fn scale(&mut self, factor: f32) {
+        self.distances.iter_mut().filter(|distance| distance.is_finite()).for_each(|distance| {
+            *distance *= factor;
+        });
+    }
+
+    /// Add `weight` times the distances of `other` to this map, without rescanning.
+    ///
+    /// Cells that are unreachable in either map become unreachable in the result.
+    /// Panics if the maps differ in size.
+    pub fn add_weighted(&mut self, other: &DijkstraMap, weight: f32) {
+        assert_eq!(self.size(), other.size(), "Dijkstra maps must have the same dimensions");
+
+        for (distance, &other_distance) in self.distances.iter_mut().zip(other.distances.iter()) {
+            if other_distance.is_finite() {
+                *distance += other_distance * weight;
+            } else {
+                *distance = Self::UNREACHABLE;
+            }
+        }
+    }
+
+    /// Build a map that leads away from the goals instead of towards them.
+    ///
+    /// Distances are multiplied by `-coefficient` and the map is rescanned, so fleeing actors
+    /// head for distant open areas rather than into the nearest dead end. A coefficient around
+    /// 1.2 is the usual choice.
+    pub fn flee_map(&self, coefficient: f32) -> Self {
+        let mut map = self.clone();
+        map.scale(-coefficient);
+        map.rescan();
+        map
+    }
+}
+
+// Navigation
+impl DijkstraMap {
+    /// Find the neighbor of `position` with the lowest distance.
+    ///
+    /// Returns `None` if `position` is out of bounds or no neighbor is strictly lower, which
+    /// means the position is already at a goal or a local minimum.
+    pub fn downhill(&self, position: impl GridCoord) -> Option<(i32, i32)> {
+        let position = position.grid_position();
+        let mut best_distance = *self.distances.get(position)?;
+        let mut best = None;
+
+        for &(dx, dy) in self.connectivity.offsets() {
+            let next = (position.0 + dx, position.1 + dy);
+            if let Some(&distance) = self.distances.get(next) {
+                if distance < best_distance {
+                    best_distance = distance;
+                    best = Some(next);
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Follow [`downhill`](Self::downhill) from `position` for at most `max_steps` steps.
+    ///
+    /// The returned path excludes `position` and stops early at a goal or local minimum.
+    pub fn downhill_path(&self, position: impl GridCoord, max_steps: usize) -> Vec<(i32, i32)> {
+        let mut path = Vec::new();
+        let mut current = position.grid_position();
+
+        while path.len() < max_steps {
+            let Some(next) = self.downhill(current) else {
+                break;
+            };
+            path.push(next);
+            current = next;
+        }
+
+        path
+    }
+}
+
+/// Heap entry ordered so the lowest distance is popped first
+#[derive(Clone, Copy)]
+struct Node {
+    distance: f32,
+    index: usize,
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Node {}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.distance.total_cmp(&self.distance).then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(_: (i32, i32), value: &char) -> bool {
+        *value != '#'
+    }
+
+    #[test]
+    fn distances_lead_to_the_nearest_goal() {
+        let grid = Grid::from_chars(".......");
+        let map = DijkstraMap::new(&grid, [(0, 0), (6, 0)], Connectivity::Four, open);
+
+        let distances: Vec<_> = (0..7).map(|x| map.get((x, 0))).collect();
+        let expected = [0.0, 1.0, 2.0, 3.0, 2.0, 1.0, 0.0].map(Some);
+        assert_eq!(distances, expected);
+    }
+
+    #[test]
+    fn unreachable_cells_have_no_distance() {
+        let grid = Grid::from_chars(
+            "
+..#..
+..#..
+",
+        );
+        let map = DijkstraMap::new(&grid, [(0, 0)], Connectivity::Eight, open);
+
+        assert_eq!(map.get((1, 1)), Some(1.0));
+        assert_eq!(map.get((2, 0)), None);
+        assert_eq!(map.get((3, 0)), None);
+        assert_eq!(map.get((9, 9)), None);
+        assert!(!map.is_reachable((4, 1)));
+    }
+
+    #[test]
+    fn downhill_walks_to_the_lowest_neighbor() {
+        let grid = Grid::from_chars(
+            "
+.....
+.###.
+.....
+",
+        );
+        let map = DijkstraMap::new(&grid, [(4, 2)], Connectivity::Four, open);
+
+        assert_eq!(map.downhill((0, 1)), Some((0, 2)));
+        assert_eq!(map.downhill((4, 2)), None);
+
+        let path = map.downhill_path((0, 0), 20);
+        assert_eq!(path.len(), 6);
+        assert_eq!(path.last(), Some(&(4, 2)));
+        assert!(path.windows(2).all(|step| map.get(step[1]) < map.get(step[0])));
+    }
+}
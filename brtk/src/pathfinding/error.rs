@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum PathError {
+    #[error("Position {0:?} is out of bounds")]
+    OutOfBounds((i32, i32)),
+    #[error("No path from {start:?} to {goal:?}")]
+    NoPath { start: (i32, i32), goal: (i32, i32) },
+}
@@ -0,0 +1,7 @@
+mod astar;
+mod dijkstra_map;
+mod error;
+
+pub use astar::*;
+pub use dijkstra_map::*;
+pub use error::*;
@@ -0,0 +1,177 @@
+use std::collections::VecDeque;
+
+use crate::{
+    grid::{Connectivity, Grid, GridCoord, GridRect},
+    region::{Region, RegionId, Regions},
+};
+
+/// Collect every cell connected to `start` for which `predicate` returns true.
+///
+/// Returns an empty `Vec` if `start` is out of bounds or does not satisfy `predicate`.
+pub fn flood_fill<T>(
+    grid: &Grid<T>,
+    start: impl GridCoord,
+    connectivity: Connectivity,
+    mut predicate: impl FnMut((i32, i32), &T) -> bool,
+) -> Vec<(i32, i32)> {
+    let mut visited = Grid::new_fill(grid.size(), false);
+    fill_from(grid, start.grid_position(), connectivity, &mut predicate, &mut visited)
+}
+
+/// Split every cell for which `predicate` returns true into connected regions.
+///
+/// Regions are numbered in row-major order of their first cell.
+pub fn label_regions<T>(
+    grid: &Grid<T>,
+    connectivity: Connectivity,
+    mut predicate: impl FnMut((i32, i32), &T) -> bool,
+) -> Regions {
+    let mut visited = Grid::new_fill(grid.size(), false);
+    let mut labels = Grid::new_fill(grid.size(), None);
+    let mut regions = Vec::new();
+
+    for index in 0..grid.data().len() {
+        if visited[index] {
+            continue;
+        }
+
+        let position = grid.index_to_position_unchecked(index);
+        let cells = fill_from(grid, position, connectivity, &mut predicate, &mut visited);
+        if cells.is_empty() {
+            continue;
+        }
+
+        let id = RegionId(regions.len() as u32);
+        for &cell in &cells {
+            labels[cell] = Some(id);
+        }
+
+        regions.push(Region { id, bounds: bounding_rect(&cells), cells });
+    }
+
+    Regions { labels, regions }
+}
+
+/// Breadth-first fill from `start`, marking every reached (or rejected) cell in `visited`
+fn fill_from<T>(
+    grid: &Grid<T>,
+    start: (i32, i32),
+    connectivity: Connectivity,
+    predicate: &mut impl FnMut((i32, i32), &T) -> bool,
+    visited: &mut Grid<bool>,
+) -> Vec<(i32, i32)> {
+    let Some(start_index) = grid.position_to_index(start) else {
+        return Vec::new();
+    };
+
+    visited[start_index] = true;
+    if !predicate(start, &grid[start_index]) {
+        return Vec::new();
+    }
+
+    let mut cells = Vec::new();
+    let mut open = VecDeque::from([start]);
+
+    while let Some(position) = open.pop_front() {
+        cells.push(position);
+
+        for &(dx, dy) in connectivity.offsets() {
+            let next = (position.0 + dx, position.1 + dy);
+            let Some(next_index) = grid.position_to_index(next) else {
+                continue;
+            };
+
+            if visited[next_index] {
+                continue;
+            }
+
+            if predicate(next, &grid[next_index]) {
+                visited[next_index] = true;
+                open.push_back(next);
+            }
+        }
+    }
+
+    cells
+}
+
+fn bounding_rect(cells: &[(i32, i32)]) -> GridRect {
+    let (mut min_x, mut min_y) = (i32::MAX, i32::MAX);
+    let (mut max_x, mut max_y) = (i32::MIN, i32::MIN);
+
+    for &(x, y) in cells {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+
+    GridRect::from_corners((min_x, min_y), (max_x, max_y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Two floor blobs that only touch diagonally, plus a separate single cell
+    const MAP: &str = "\
+..###
+..###
+##..#
+##..#
+####.";
+
+    fn grid() -> Grid<char> {
+        Grid::from_text(MAP, Some).unwrap()
+    }
+
+    fn floor(_: (i32, i32), tile: &char) -> bool {
+        *tile == '.'
+    }
+
+    #[test]
+    fn flood_fill_respects_connectivity() {
+        let grid = grid();
+        assert_eq!(flood_fill(&grid, (0, 0), Connectivity::Four, floor).len(), 4);
+        assert_eq!(flood_fill(&grid, (0, 0), Connectivity::Eight, floor).len(), 9);
+    }
+
+    #[test]
+    fn flood_fill_from_rejected_or_out_of_bounds_start_is_empty() {
+        let grid = grid();
+        assert!(flood_fill(&grid, (4, 0), Connectivity::Eight, floor).is_empty());
+        assert!(flood_fill(&grid, (-1, 0), Connectivity::Eight, floor).is_empty());
+    }
+
+    #[test]
+    fn label_regions_with_four_connectivity() {
+        let regions = label_regions(&grid(), Connectivity::Four, floor);
+        assert_eq!(regions.len(), 3);
+        let sizes: Vec<_> = regions.regions.iter().map(Region::size).collect();
+        assert_eq!(sizes, vec![4, 4, 1]);
+        assert_eq!(regions.regions[1].bounds, GridRect::new(2, 2, 2, 2));
+        assert_eq!(regions.labels[(4, 4)], Some(RegionId(2)));
+        assert_eq!(regions.labels[(4, 0)], None);
+    }
+
+    #[test]
+    fn label_regions_with_eight_connectivity() {
+        let regions = label_regions(&grid(), Connectivity::Eight, floor);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions.regions[0].size(), 9);
+        assert_eq!(regions.regions[0].bounds, GridRect::new(0, 0, 5, 5));
+    }
+
+    #[test]
+    fn region_at_and_largest() {
+        let mut grid = grid();
+        grid[(0, 0)] = '#';
+        let regions = label_regions(&grid, Connectivity::Four, floor);
+        let largest = regions.largest().unwrap();
+        assert_eq!(largest.size(), 4);
+        assert_eq!(regions.region_at((3, 3)).map(|region| region.id), Some(largest.id));
+        assert_eq!(regions.region_at((1, 1)).map(Region::size), Some(3));
+        assert!(regions.region_at((0, 0)).is_none());
+        assert!(regions.region_at((9, 9)).is_none());
+    }
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::grid::{Grid, GridCoord, GridRect};
+
+/// Identifies a connected region found by [`label_regions`](crate::region::label_regions)
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RegionId(pub u32);
+
+impl RegionId {
+    /// The index of this region in [`Regions::regions`]
+    #[inline]
+    pub const fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A single connected region of cells
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone)]
+pub struct Region {
+    pub id: RegionId,
+    /// Smallest rect containing every cell of this region
+    pub bounds: GridRect,
+    /// Cells of this region, in the order the flood fill reached them
+    pub cells: Vec<(i32, i32)>,
+}
+
+impl Region {
+    /// Number of cells in this region
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+/// The result of [`label_regions`](crate::region::label_regions): a label per cell plus a
+/// description of every region
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone)]
+pub struct Regions {
+    pub labels: Grid<Option<RegionId>>,
+    pub regions: Vec<Region>,
+}
+
+impl Regions {
+    /// Obtain the region a position belongs to, if any
+    pub fn region_at(&self, position: impl GridCoord) -> Option<&Region> {
+        self.labels.get(position).copied().flatten().map(|id| &self.regions[id.index()])
+    }
+
+    /// Obtain the region with the most cells, if any
+    pub fn largest(&self) -> Option<&Region> {
+        self.regions.iter().max_by_key(|region| region.size())
+    }
+
+    /// Number of regions found
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Determine if no region was found
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
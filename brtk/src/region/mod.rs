@@ -0,0 +1,5 @@
+mod flood_fill;
+mod labels;
+
+pub use flood_fill::*;
+pub use labels::*;
@@ -58,6 +58,8 @@ pub struct AppSettings {
     // Gamepad
 
     // Gameplay
+    /// Seed for all of the game's randomness. A random seed is picked when unset.
+    pub seed: Option<u64>,
     // Companions
     // Health Warning
     // Auto-explore
@@ -65,7 +67,7 @@ pub struct AppSettings {
 
 impl Default for AppSettings {
     fn default() -> Self {
-        Self { tile_size: 16, fullscreen: false, view_size: (60, 40) }
+        Self { tile_size: 16, fullscreen: false, view_size: (60, 40), seed: None }
     }
 }
 
@@ -75,6 +77,28 @@ impl AppSettings {
         self.fullscreen
     }
 
+    #[must_use]
+    pub const fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Overrides settings from command line arguments, such as `--seed 42` or `--seed=42`
+    pub fn apply_args(&mut self, args: impl IntoIterator<Item = String>) {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let seed = match arg.strip_prefix("--seed") {
+                Some("") => args.next(),
+                Some(value) => value.strip_prefix('=').map(str::to_owned),
+                None => continue,
+            };
+
+            match seed.as_deref().map(str::parse) {
+                Some(Ok(seed)) => self.seed = Some(seed),
+                _ => log::warn!("Ignoring invalid seed argument: {:?}", seed),
+            }
+        }
+    }
+
     // #[must_use]
     // pub const fn window_width(&self) -> f32 {
     //     (self.tile_size * self.view_size.0 + UiConstants::STATS_WIDTH) as f32
@@ -2,8 +2,9 @@ use bevy::{prelude::*, utils::HashMap};
 use once_cell::sync::Lazy;
 
 use crate::model::{
-    actions::Walk,
-    components::{AwaitingInput, TurnActor},
+    actions::{Ascend, Attack, CloseDoor, Descend, OpenDoor, Rest, Wait, Walk},
+    components::{AwaitingInput, Position, TerrainType, TurnActor},
+    resources::CurrentMap,
     types::{ActionType, BuildableGameAction, GameActionBuilder, MoveDirection},
     GameState,
 };
@@ -19,11 +20,30 @@ macro_rules! action_keys {
 }
 
 static ACTION_KEYS: Lazy<HashMap<ActionType, Vec<KeyCode>>> = action_keys![
-    (ActionType::Move(MoveDirection::North), [KeyCode::KeyW, KeyCode::ArrowUp]),
-    (ActionType::Move(MoveDirection::South), [KeyCode::KeyS, KeyCode::ArrowDown]),
-    (ActionType::Move(MoveDirection::West), [KeyCode::KeyA, KeyCode::ArrowLeft]),
-    (ActionType::Move(MoveDirection::East), [KeyCode::KeyD, KeyCode::ArrowRight]),
-    (ActionType::Wait, [KeyCode::Space, KeyCode::Period])
+    (
+        ActionType::Move(MoveDirection::North),
+        [KeyCode::KeyW, KeyCode::ArrowUp, KeyCode::Numpad8, KeyCode::KeyK]
+    ),
+    (
+        ActionType::Move(MoveDirection::South),
+        [KeyCode::KeyS, KeyCode::ArrowDown, KeyCode::Numpad2, KeyCode::KeyJ]
+    ),
+    (
+        ActionType::Move(MoveDirection::West),
+        [KeyCode::KeyA, KeyCode::ArrowLeft, KeyCode::Numpad4, KeyCode::KeyH]
+    ),
+    (
+        ActionType::Move(MoveDirection::East),
+        [KeyCode::KeyD, KeyCode::ArrowRight, KeyCode::Numpad6, KeyCode::KeyL]
+    ),
+    (ActionType::Move(MoveDirection::NorthWest), [KeyCode::Numpad7, KeyCode::KeyY]),
+    (ActionType::Move(MoveDirection::NorthEast), [KeyCode::Numpad9, KeyCode::KeyU]),
+    (ActionType::Move(MoveDirection::SouthWest), [KeyCode::Numpad1, KeyCode::KeyB]),
+    (ActionType::Move(MoveDirection::SouthEast), [KeyCode::Numpad3, KeyCode::KeyN]),
+    (ActionType::Wait, [KeyCode::Space, KeyCode::Period, KeyCode::Numpad5]),
+    (ActionType::Rest, [KeyCode::KeyR]),
+    (ActionType::OpenDoor, [KeyCode::KeyO]),
+    (ActionType::CloseDoor, [KeyCode::KeyC])
 ];
 
 /// System that handles player input and converts it into game actions
@@ -32,22 +52,66 @@ pub fn player_input_system(
     input: Res<ButtonInput<KeyCode>>,
     mut next_state: ResMut<NextState<GameState>>,
     q_awaiting_input: Option<Single<(Entity, &mut TurnActor), With<AwaitingInput>>>,
+    current_map: Res<CurrentMap>,
+    q_position: Query<&Position>,
+    q_terrain: Query<&TerrainType>,
 ) {
     if let Some(a) = q_awaiting_input {
         let (entity, mut p_actor) = a.into_inner();
         let mut action: Option<ActionType> = None;
 
-        for (act, keys) in ACTION_KEYS.iter() {
-            if keys.iter().any(|key| input.just_pressed(*key)) {
-                log::info!("Player input: {:?}", act);
-                action = Some(*act);
-                break;
+        // '>' and '<' share their keys with other actions, so check them first
+        let shift = input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        if shift && input.just_pressed(KeyCode::Period) {
+            action = Some(ActionType::Descend);
+        } else if shift && input.just_pressed(KeyCode::Comma) {
+            action = Some(ActionType::Ascend);
+        } else {
+            for (act, keys) in ACTION_KEYS.iter() {
+                if keys.iter().any(|key| input.just_pressed(*key)) {
+                    log::info!("Player input: {:?}", act);
+                    action = Some(*act);
+                    break;
+                }
             }
         }
 
         if let Some(act) = &action {
-            if let ActionType::Move(dir) = act {
-                p_actor.add_action(Walk::builder().with_entity(entity).with_direction((*dir).into()).build());
+            let find_adjacent =
+                |terrain_type| adjacent_terrain(entity, terrain_type, &current_map, &q_position, &q_terrain);
+
+            match act {
+                ActionType::Move(dir) => {
+                    let walk = Walk::builder().with_entity(entity).with_direction((*dir).into()).build();
+                    p_actor.add_action(walk);
+                }
+                ActionType::Wait => p_actor.add_action(Wait::builder().with_entity(entity).build()),
+                ActionType::Rest => p_actor.add_action(Rest::builder().with_entity(entity).build()),
+                ActionType::Descend => p_actor.add_action(Descend::builder().with_entity(entity).build()),
+                ActionType::Ascend => p_actor.add_action(Ascend::builder().with_entity(entity).build()),
+                ActionType::OpenDoor => {
+                    // Open the first closed door next to the player
+                    let Some(direction) = find_adjacent(TerrainType::Door) else {
+                        log::info!("There is no closed door nearby");
+                        return;
+                    };
+
+                    let action = OpenDoor::builder().with_entity(entity).with_direction(direction).build();
+                    p_actor.add_action(action);
+                }
+                ActionType::CloseDoor => {
+                    // Close the first open door next to the player
+                    let Some(direction) = find_adjacent(TerrainType::OpenDoor) else {
+                        log::info!("There is no open door nearby");
+                        return;
+                    };
+
+                    let action = CloseDoor::builder().with_entity(entity).with_direction(direction).build();
+                    p_actor.add_action(action);
+                }
+                ActionType::Attack(target) => {
+                    p_actor.add_action(Attack::builder().with_entity(entity).with_target(*target).build());
+                }
             }
 
             commands.entity(entity).remove::<AwaitingInput>();
@@ -55,3 +119,23 @@ pub fn player_input_system(
         }
     }
 }
+
+/// The direction from `entity` towards the first adjacent tile of the given terrain type
+fn adjacent_terrain(
+    entity: Entity,
+    terrain_type: TerrainType,
+    current_map: &CurrentMap,
+    q_position: &Query<&Position>,
+    q_terrain: &Query<&TerrainType>,
+) -> Option<IVec2> {
+    let position = *q_position.get(entity).ok()?;
+    MoveDirection::all_directions()
+        .into_iter()
+        .find(|&direction| {
+            current_map
+                .get_terrain(position + direction)
+                .and_then(|terrain_entity| q_terrain.get(terrain_entity).ok())
+                .is_some_and(|terrain| *terrain == terrain_type)
+        })
+        .map(IVec2::from)
+}
@@ -38,7 +38,8 @@ fn main() {
     let mut app = App::new();
 
     // Load AppSettings
-    let app_settings = AppSettings::default();
+    let mut app_settings = AppSettings::default();
+    app_settings.apply_args(std::env::args().skip(1));
 
     app.add_plugins(
         DefaultPlugins
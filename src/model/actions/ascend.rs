@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use crate::{
+    impl_debug_with_field, impl_game_action,
+    model::{
+        actions::descend::standing_on,
+        components::TerrainType,
+        resources::Dungeon,
+        types::{base_action_cost, GameAction, GameError},
+        utils::change_level,
+    },
+};
+
+#[derive(Default)]
+pub struct AscendBuilder {
+    entity: Option<Entity>,
+}
+
+impl AscendBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_entity(mut self, entity: Entity) -> Self {
+        self.entity = Some(entity);
+        self
+    }
+}
+
+/// Takes the up stairs the entity stands on, arriving on the down stairs of the previous level.
+/// There is no way out of the first level.
+pub struct Ascend {
+    entity: Entity,
+}
+
+impl GameAction for Ascend {
+    fn entity(&self) -> Option<Entity> {
+        Some(self.entity)
+    }
+
+    fn perform(&self, world: &mut World) -> Result<u64, GameError> {
+        if standing_on(world, self.entity) != Some(TerrainType::UpStairs) {
+            return Err(GameError::NoStairs);
+        }
+
+        let depth = world.resource::<Dungeon>().depth();
+        if depth <= 1 {
+            log::info!("The way out is sealed");
+            return Err(GameError::NoStairs);
+        }
+
+        let level_change = change_level(world, self.entity, depth - 1, TerrainType::DownStairs)?;
+        // Picked up by process_turns, which holds the turn queue while actions are performed
+        world.insert_resource(level_change);
+
+        Ok(base_action_cost(world, self.entity))
+    }
+}
+
+impl_debug_with_field!(Ascend, entity);
+impl_game_action!(Ascend, AscendBuilder, entity);
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use crate::{
+    impl_debug_with_field, impl_game_action,
+    model::{
+        components::{AITag, CombatStats, DeadTag, Health, PlayerTag},
+        types::{base_action_cost, GameAction, GameError},
+        utils::kill_entity,
+    },
+};
+
+#[derive(Default)]
+pub struct AttackBuilder {
+    entity: Option<Entity>,
+    target: Option<Entity>,
+}
+
+impl AttackBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_entity(mut self, entity: Entity) -> Self {
+        self.entity = Some(entity);
+        self
+    }
+
+    pub fn with_target(mut self, target: Entity) -> Self {
+        self.target = Some(target);
+        self
+    }
+}
+
+pub struct Attack {
+    entity: Entity,
+    target: Entity,
+}
+
+impl Attack {
+    /// Returns true if `attacker` would attack `target` when bumping into it.
+    /// The player and AI controlled actors are hostile to each other.
+    pub fn is_hostile(world: &World, attacker: Entity, target: Entity) -> bool {
+        let is_player = |entity| world.get::<PlayerTag>(entity).is_some();
+        let is_ai = |entity| world.get::<AITag>(entity).is_some();
+
+        world.get::<Health>(target).is_some()
+            && ((is_player(attacker) && is_ai(target)) || (is_ai(attacker) && is_player(target)))
+    }
+}
+
+impl GameAction for Attack {
+    fn entity(&self) -> Option<Entity> {
+        Some(self.entity)
+    }
+
+    fn perform(&self, world: &mut World) -> Result<u64, GameError> {
+        if world.get::<DeadTag>(self.target).is_some() {
+            return Err(GameError::InvalidTarget);
+        }
+
+        let Some(attack) = world.get::<CombatStats>(self.entity).map(|stats| stats.attack) else {
+            log::error!("Attacker has no combat stats: {}", self.entity);
+            return Err(GameError::MissingComponent);
+        };
+        let defense = world.get::<CombatStats>(self.target).map_or(0, |stats| stats.defense);
+
+        let Some(mut health) = world.get_mut::<Health>(self.target) else {
+            log::error!("Target has no health: {}", self.target);
+            return Err(GameError::InvalidTarget);
+        };
+
+        let damage = health.take_damage(attack - defense);
+        let is_dead = health.is_dead();
+        log::info!("{} hits {} for {} damage", self.entity, self.target, damage);
+
+        if is_dead {
+            log::info!("{} dies", self.target);
+            kill_entity(world, self.target);
+        }
+
+        Ok(base_action_cost(world, self.entity))
+    }
+}
+
+impl_debug_with_field!(Attack, target);
+impl_game_action!(Attack, AttackBuilder, entity, target);
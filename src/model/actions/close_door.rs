@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+
+use crate::{
+    impl_debug_with_field, impl_game_action,
+    model::{
+        actions::open_door::set_door_state,
+        components::{Position, TerrainType},
+        resources::CurrentMap,
+        types::{base_action_cost, GameAction, GameError},
+    },
+};
+
+#[derive(Default)]
+pub struct CloseDoorBuilder {
+    entity: Option<Entity>,
+    direction: Option<IVec2>,
+}
+
+impl CloseDoorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_entity(mut self, entity: Entity) -> Self {
+        self.entity = Some(entity);
+        self
+    }
+
+    pub fn with_direction(mut self, direction: IVec2) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+}
+
+/// Closes the open door next to the entity in the given direction. Fails if anything lies in
+/// the doorway.
+pub struct CloseDoor {
+    entity: Entity,
+    direction: IVec2,
+}
+
+impl GameAction for CloseDoor {
+    fn entity(&self) -> Option<Entity> {
+        Some(self.entity)
+    }
+
+    fn perform(&self, world: &mut World) -> Result<u64, GameError> {
+        let Some(position) = world.get::<Position>(self.entity).map(|position| *position + self.direction)
+        else {
+            return Err(GameError::EntityNotFound);
+        };
+
+        if let Some(occupant) = world.resource::<CurrentMap>().entities.entities_at(position).next() {
+            log::info!("{} is in the doorway", occupant);
+            return Err(GameError::TileOccupied(occupant));
+        }
+
+        set_door_state(world, position, TerrainType::OpenDoor, TerrainType::Door)?;
+        log::info!("Entity {} closes the door at {:?}", self.entity, position);
+
+        Ok(base_action_cost(world, self.entity))
+    }
+}
+
+impl_debug_with_field!(CloseDoor, direction);
+impl_game_action!(CloseDoor, CloseDoorBuilder, entity, direction);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        resources::FovMap,
+        test_utils::{set_terrain, setup_world, spawn_actor},
+        types::{BuildableGameAction, GameActionBuilder},
+    };
+
+    fn close_door(world: &mut World, entity: Entity, direction: IVec2) -> Result<u64, GameError> {
+        CloseDoor::builder().with_entity(entity).with_direction(direction).build().perform(world)
+    }
+
+    fn terrain_at(world: &World, position: Position) -> TerrainType {
+        let terrain_entity = world.resource::<CurrentMap>().get_terrain(position).unwrap();
+        world.get::<TerrainType>(terrain_entity).unwrap().clone()
+    }
+
+    #[test]
+    fn close_door_updates_masks_and_blocks_sight() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 2));
+        let door = Position::new(2, 2);
+        set_terrain(&mut world, door, TerrainType::OpenDoor);
+
+        let mut fov = FovMap::new(5, 5);
+        fov.compute_fov(world.resource::<CurrentMap>(), Position::new(1, 2), 8);
+        assert!(fov.is_visible(Position::new(3, 2)));
+
+        assert!(close_door(&mut world, actor, IVec2::X).is_ok());
+        assert!(terrain_at(&world, door) == TerrainType::Door);
+
+        let map = world.resource::<CurrentMap>();
+        assert!(!map.is_walkable(door));
+        assert!(map.is_opaque(door));
+
+        fov.compute_fov(map, Position::new(1, 2), 8);
+        assert!(fov.is_visible(door));
+        assert!(!fov.is_visible(Position::new(3, 2)));
+        assert!(fov.is_revealed(Position::new(3, 2)));
+    }
+
+    #[test]
+    fn close_door_on_an_occupied_tile_is_refused() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 2));
+        let blocker = spawn_actor(&mut world, Position::new(2, 2));
+        set_terrain(&mut world, Position::new(2, 2), TerrainType::OpenDoor);
+
+        let result = close_door(&mut world, actor, IVec2::X);
+        assert!(matches!(result, Err(GameError::TileOccupied(e)) if e == blocker));
+        assert!(terrain_at(&world, Position::new(2, 2)) == TerrainType::OpenDoor);
+        assert!(world.resource::<CurrentMap>().is_walkable(Position::new(2, 2)));
+    }
+
+    #[test]
+    fn close_door_without_an_open_door_is_refused() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 2));
+
+        assert!(matches!(close_door(&mut world, actor, IVec2::X), Err(GameError::InvalidTarget)));
+    }
+}
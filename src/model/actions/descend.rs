@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use crate::{
+    impl_debug_with_field, impl_game_action,
+    model::{
+        components::{Position, TerrainType},
+        resources::{CurrentMap, Dungeon},
+        types::{base_action_cost, GameAction, GameError},
+        utils::change_level,
+    },
+};
+
+#[derive(Default)]
+pub struct DescendBuilder {
+    entity: Option<Entity>,
+}
+
+impl DescendBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_entity(mut self, entity: Entity) -> Self {
+        self.entity = Some(entity);
+        self
+    }
+}
+
+/// Takes the down stairs the entity stands on, arriving on the up stairs of the next level
+pub struct Descend {
+    entity: Entity,
+}
+
+impl GameAction for Descend {
+    fn entity(&self) -> Option<Entity> {
+        Some(self.entity)
+    }
+
+    fn perform(&self, world: &mut World) -> Result<u64, GameError> {
+        if standing_on(world, self.entity) != Some(TerrainType::DownStairs) {
+            return Err(GameError::NoStairs);
+        }
+
+        let depth = world.resource::<Dungeon>().depth() + 1;
+        let level_change = change_level(world, self.entity, depth, TerrainType::UpStairs)?;
+        // Picked up by process_turns, which holds the turn queue while actions are performed
+        world.insert_resource(level_change);
+
+        Ok(base_action_cost(world, self.entity))
+    }
+}
+
+/// The terrain type under an entity
+pub(super) fn standing_on(world: &World, entity: Entity) -> Option<TerrainType> {
+    let position = world.get::<Position>(entity)?;
+    let terrain_entity = world.resource::<CurrentMap>().get_terrain(*position)?;
+    world.get::<TerrainType>(terrain_entity).cloned()
+}
+
+impl_debug_with_field!(Descend, entity);
+impl_game_action!(Descend, DescendBuilder, entity);
@@ -1,3 +1,21 @@
+mod ascend;
+pub use ascend::*;
+
+mod attack;
+pub use attack::*;
+
+mod close_door;
+pub use close_door::*;
+
+mod descend;
+pub use descend::*;
+
+mod open_door;
+pub use open_door::*;
+
+mod rest;
+pub use rest::*;
+
 mod walk;
 pub use walk::*;
 
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::{
+    impl_debug_with_field, impl_game_action,
+    model::{
+        components::{Description, Position, TerrainType},
+        resources::CurrentMap,
+        types::{base_action_cost, GameAction, GameError},
+    },
+};
+
+#[derive(Default)]
+pub struct OpenDoorBuilder {
+    entity: Option<Entity>,
+    direction: Option<IVec2>,
+}
+
+impl OpenDoorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_entity(mut self, entity: Entity) -> Self {
+        self.entity = Some(entity);
+        self
+    }
+
+    pub fn with_direction(mut self, direction: IVec2) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+}
+
+/// Opens the closed door next to the entity in the given direction
+pub struct OpenDoor {
+    entity: Entity,
+    direction: IVec2,
+}
+
+impl GameAction for OpenDoor {
+    fn entity(&self) -> Option<Entity> {
+        Some(self.entity)
+    }
+
+    fn perform(&self, world: &mut World) -> Result<u64, GameError> {
+        let Some(position) = world.get::<Position>(self.entity).map(|position| *position + self.direction)
+        else {
+            return Err(GameError::EntityNotFound);
+        };
+
+        set_door_state(world, position, TerrainType::Door, TerrainType::OpenDoor)?;
+        log::info!("Entity {} opens the door at {:?}", self.entity, position);
+
+        Ok(base_action_cost(world, self.entity))
+    }
+}
+
+/// Switches the door at `position` from the `from` to the `to` terrain type, keeping the map's
+/// walkable and opaque masks up to date
+pub(super) fn set_door_state(
+    world: &mut World,
+    position: Position,
+    from: TerrainType,
+    to: TerrainType,
+) -> Result<(), GameError> {
+    let Some(terrain_entity) = world.resource::<CurrentMap>().get_terrain(position) else {
+        return Err(GameError::InvalidPosition);
+    };
+
+    let Some(mut terrain_type) = world.get_mut::<TerrainType>(terrain_entity) else {
+        return Err(GameError::MissingComponent);
+    };
+
+    if *terrain_type != from {
+        return Err(GameError::InvalidTarget);
+    }
+
+    *terrain_type = to.clone();
+
+    let description = match to {
+        TerrainType::OpenDoor => Description::new("Open door"),
+        _ => Description::new("Door"),
+    };
+    world.entity_mut(terrain_entity).insert(description);
+    world.resource_mut::<CurrentMap>().update_masks(position, &to);
+
+    Ok(())
+}
+
+impl_debug_with_field!(OpenDoor, direction);
+impl_game_action!(OpenDoor, OpenDoorBuilder, entity, direction);
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+use crate::{
+    impl_debug_with_field, impl_game_action,
+    model::{
+        components::{AITag, DeadTag, Health, PlayerTag, Position, TurnActor},
+        resources::FovMap,
+        types::{base_action_cost, GameAction, GameError},
+    },
+};
+
+#[derive(Default)]
+pub struct RestBuilder {
+    entity: Option<Entity>,
+}
+
+impl RestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_entity(mut self, entity: Entity) -> Self {
+        self.entity = Some(entity);
+        self
+    }
+}
+
+/// Waits and heals one hit point per turn, queueing itself again until the entity is fully
+/// healed or, for the player, a hostile comes into view
+pub struct Rest {
+    entity: Entity,
+}
+
+impl Rest {
+    fn should_stop(&self, world: &mut World) -> bool {
+        match world.get::<Health>(self.entity) {
+            Some(health) if !health.is_full() => self.hostile_in_view(world),
+            _ => true,
+        }
+    }
+
+    fn hostile_in_view(&self, world: &mut World) -> bool {
+        if world.get::<PlayerTag>(self.entity).is_none() {
+            return false;
+        }
+
+        let mut q_hostiles = world.query_filtered::<&Position, (With<AITag>, Without<DeadTag>)>();
+        let fov_map = world.resource::<FovMap>();
+        q_hostiles.iter(world).any(|position| fov_map.is_visible(*position))
+    }
+}
+
+impl GameAction for Rest {
+    fn entity(&self) -> Option<Entity> {
+        Some(self.entity)
+    }
+
+    fn perform(&self, world: &mut World) -> Result<u64, GameError> {
+        if self.should_stop(world) {
+            log::info!("Entity {} stops resting", self.entity);
+            return Err(GameError::CannotRest);
+        }
+
+        if let Some(mut health) = world.get_mut::<Health>(self.entity) {
+            health.heal(1);
+        }
+
+        // Keep resting on the next turn unless this turn was enough
+        if !self.should_stop(world) {
+            if let Some(mut actor) = world.get_mut::<TurnActor>(self.entity) {
+                actor.actions.push_front(Box::new(Rest { entity: self.entity }));
+            }
+        }
+
+        Ok(base_action_cost(world, self.entity))
+    }
+}
+
+impl_debug_with_field!(Rest, entity);
+impl_game_action!(Rest, RestBuilder, entity);
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 use crate::{
     impl_debug_with_field, impl_game_action,
-    model::types::{GameAction, GameError},
+    model::types::{base_action_cost, GameAction, GameError},
 };
 
 #[derive(Default)]
@@ -29,9 +29,10 @@ impl GameAction for Wait {
     fn entity(&self) -> Option<Entity> {
         Some(self.entity)
     }
-    fn perform(&self, _world: &mut World) -> Result<u64, GameError> {
+
+    fn perform(&self, world: &mut World) -> Result<u64, GameError> {
         log::info!("Entity {} is waiting", self.entity);
-        Ok(1000)
+        Ok(base_action_cost(world, self.entity))
     }
 }
 
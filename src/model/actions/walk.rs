@@ -3,9 +3,10 @@ use bevy::{ecs::system::SystemState, prelude::*};
 use crate::{
     impl_debug_with_field, impl_game_action,
     model::{
-        components::{Position, TerrainType},
-        resources::CurrentMap,
-        types::{GameAction, GameError},
+        actions::{Attack, AttackBuilder, OpenDoorBuilder},
+        components::{MapLayer, Position, TerrainType},
+        resources::{CurrentMap, DiagonalRule},
+        types::{base_action_cost, GameAction, GameActionBuilder, GameError},
     },
 };
 
@@ -36,40 +37,81 @@ pub struct Walk {
     direction: IVec2,
 }
 
+impl Walk {
+    // The hostile actor standing on the destination tile, if any
+    fn hostile_target(&self, world: &World) -> Option<Entity> {
+        let position = *world.get::<Position>(self.entity)? + self.direction;
+        let occupant = world.resource::<CurrentMap>().get_actor(position).filter(|&e| e != self.entity)?;
+        Attack::is_hostile(world, self.entity, occupant).then_some(occupant)
+    }
+
+    // Whether the destination tile is a closed door
+    fn is_closed_door(&self, world: &World) -> bool {
+        let Some(position) = world.get::<Position>(self.entity).map(|position| *position + self.direction)
+        else {
+            return false;
+        };
+
+        world
+            .resource::<CurrentMap>()
+            .get_terrain(position)
+            .and_then(|terrain_entity| world.get::<TerrainType>(terrain_entity))
+            .is_some_and(|terrain_type| *terrain_type == TerrainType::Door)
+    }
+}
+
 impl GameAction for Walk {
     fn entity(&self) -> Option<Entity> {
         Some(self.entity)
     }
 
     fn perform(&self, world: &mut World) -> Result<u64, GameError> {
-        let mut state: SystemState<(ResMut<CurrentMap>, Query<&mut Position>, Query<&TerrainType>)> =
-            SystemState::new(world);
+        let diagonal_rule = world.get_resource::<DiagonalRule>().copied().unwrap_or_default();
+        if let Some(position) = world.get::<Position>(self.entity) {
+            if !diagonal_rule.allows(world.resource::<CurrentMap>(), *position, self.direction) {
+                log::info!("Diagonal move not allowed: {:?}", self.direction);
+                return Err(GameError::DiagonalBlocked);
+            }
+        }
+
+        // Bumping into a hostile actor attacks it instead
+        if let Some(target) = self.hostile_target(world) {
+            return AttackBuilder::new().with_entity(self.entity).with_target(target).build().perform(world);
+        }
+
+        // Bumping into a closed door opens it
+        if self.is_closed_door(world) {
+            let open_door = OpenDoorBuilder::new().with_entity(self.entity).with_direction(self.direction);
+            return open_door.build().perform(world);
+        }
+
+        let mut state: SystemState<(ResMut<CurrentMap>, Query<&mut Position>)> = SystemState::new(world);
 
         // Get references to the data
-        let (current_map, mut q_position, q_terrain_type) = state.get_mut(world);
+        let (mut current_map, mut q_position) = state.get_mut(world);
 
         // Get the entity's current position
         if let Ok(mut current_pos) = q_position.get_mut(self.entity) {
             let new_pos = *current_pos + self.direction;
 
-            let Some(terrain_entity) = current_map.get_terrain(new_pos) else {
+            if !current_map.in_bounds(new_pos) {
                 log::error!("Failed to get terrain for entity: {}", self.entity);
                 return Err(GameError::InvalidPosition);
-            };
-
-            let Ok(terrain_type) = q_terrain_type.get(terrain_entity) else {
-                log::error!("Failed to get terrain type for entity: {}", self.entity);
-                return Err(GameError::MissingComponent);
-            };
-
-            match terrain_type {
-                TerrainType::Wall => {
-                    log::error!("Wall in the way");
-                    return Err(GameError::TerrainBlocked);
-                }
-                _ => {
-                    *current_pos = new_pos;
-                }
+            }
+
+            if !current_map.is_walkable(new_pos) {
+                log::error!("Wall in the way");
+                return Err(GameError::TerrainBlocked);
+            }
+
+            if let Some(occupant) = current_map.get_actor(new_pos).filter(|&e| e != self.entity) {
+                log::info!("{} is in the way", occupant);
+                return Err(GameError::TileOccupied(occupant));
+            }
+
+            *current_pos = new_pos;
+            if !current_map.entities.move_entity(self.entity, new_pos) {
+                current_map.entities.insert(self.entity, new_pos, MapLayer::Actor);
             }
         } else {
             return Err(GameError::EntityNotFound);
@@ -78,9 +120,129 @@ impl GameAction for Walk {
         // Return the system state to update the world
         state.apply(world);
 
-        Ok(1000)
+        Ok(base_action_cost(world, self.entity))
     }
 }
 
 impl_debug_with_field!(Walk, direction);
 impl_game_action!(Walk, WalkBuilder, entity, direction);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        components::{AITag, CombatStats, DeadTag, Health, PlayerTag},
+        test_utils::{set_terrain, setup_world, spawn_actor},
+        types::BuildableGameAction,
+    };
+
+    fn walk(world: &mut World, entity: Entity, direction: IVec2) -> Result<u64, GameError> {
+        Walk::builder().with_entity(entity).with_direction(direction).build().perform(world)
+    }
+
+    #[test]
+    fn walk_moves_occupancy_with_the_actor() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 1));
+
+        assert!(walk(&mut world, actor, IVec2::X).is_ok());
+
+        let map = world.resource::<CurrentMap>();
+        assert_eq!(*world.get::<Position>(actor).unwrap(), Position::new(2, 1));
+        assert_eq!(map.get_actor(Position::new(2, 1)), Some(actor));
+        assert_eq!(map.get_actor(Position::new(1, 1)), None);
+    }
+
+    #[test]
+    fn walk_into_occupied_tile_is_refused() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 1));
+        let blocker = spawn_actor(&mut world, Position::new(2, 1));
+
+        assert!(matches!(walk(&mut world, actor, IVec2::X), Err(GameError::TileOccupied(e)) if e == blocker));
+
+        let map = world.resource::<CurrentMap>();
+        assert_eq!(*world.get::<Position>(actor).unwrap(), Position::new(1, 1));
+        assert_eq!(map.get_actor(Position::new(1, 1)), Some(actor));
+        assert_eq!(map.get_actor(Position::new(2, 1)), Some(blocker));
+    }
+
+    #[test]
+    fn walk_onto_items_is_allowed() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 1));
+        let item = world.spawn((Position::new(2, 1), MapLayer::Item)).id();
+        world.resource_mut::<CurrentMap>().entities.insert(item, Position::new(2, 1), MapLayer::Item);
+
+        assert!(walk(&mut world, actor, IVec2::X).is_ok());
+        assert_eq!(world.resource::<CurrentMap>().get_actor(Position::new(2, 1)), Some(actor));
+    }
+
+    #[test]
+    fn walk_into_wall_is_refused() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 1));
+
+        assert!(matches!(walk(&mut world, actor, IVec2::NEG_X), Err(GameError::TerrainBlocked)));
+        assert_eq!(world.resource::<CurrentMap>().get_actor(Position::new(1, 1)), Some(actor));
+    }
+
+    #[test]
+    fn walk_into_hostile_attacks_until_it_dies() {
+        let mut world = setup_world();
+        let player = spawn_actor(&mut world, Position::new(1, 1));
+        let monster = spawn_actor(&mut world, Position::new(2, 1));
+        world.entity_mut(player).insert((PlayerTag, Health::new(10), CombatStats::new(3, 0)));
+        world.entity_mut(monster).insert((AITag, Health::new(5), CombatStats::new(1, 1)));
+
+        assert!(walk(&mut world, player, IVec2::X).is_ok());
+        assert_eq!(world.get::<Health>(monster).unwrap().current, 3);
+        assert_eq!(*world.get::<Position>(player).unwrap(), Position::new(1, 1));
+
+        assert!(walk(&mut world, player, IVec2::X).is_ok());
+        assert!(walk(&mut world, player, IVec2::X).is_ok());
+        assert!(world.get::<DeadTag>(monster).is_some());
+
+        // The corpse no longer blocks the tile
+        assert!(walk(&mut world, player, IVec2::X).is_ok());
+        assert_eq!(world.resource::<CurrentMap>().get_actor(Position::new(2, 1)), Some(player));
+    }
+
+    #[test]
+    fn diagonal_walk_follows_the_diagonal_rule() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 1));
+
+        // A wall next to the actor blocks cutting the corner
+        world.resource_mut::<CurrentMap>().update_masks(Position::new(2, 1), &TerrainType::Wall);
+        assert!(matches!(walk(&mut world, actor, IVec2::ONE), Err(GameError::DiagonalBlocked)));
+
+        world.insert_resource(DiagonalRule::Always);
+        assert!(walk(&mut world, actor, IVec2::ONE).is_ok());
+        assert_eq!(*world.get::<Position>(actor).unwrap(), Position::new(2, 2));
+
+        world.insert_resource(DiagonalRule::Never);
+        assert!(matches!(walk(&mut world, actor, IVec2::NEG_ONE), Err(GameError::DiagonalBlocked)));
+    }
+
+    #[test]
+    fn walk_into_closed_door_opens_it() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 1));
+        set_terrain(&mut world, Position::new(2, 1), TerrainType::Door);
+
+        // The first bump opens the door without moving
+        assert!(walk(&mut world, actor, IVec2::X).is_ok());
+        assert_eq!(*world.get::<Position>(actor).unwrap(), Position::new(1, 1));
+
+        let map = world.resource::<CurrentMap>();
+        let door = map.get_terrain(Position::new(2, 1)).unwrap();
+        assert!(*world.get::<TerrainType>(door).unwrap() == TerrainType::OpenDoor);
+        assert!(map.is_walkable(Position::new(2, 1)));
+        assert!(!map.is_opaque(Position::new(2, 1)));
+
+        // The second one walks through it
+        assert!(walk(&mut world, actor, IVec2::X).is_ok());
+        assert_eq!(*world.get::<Position>(actor).unwrap(), Position::new(2, 1));
+    }
+}
@@ -1,8 +1,8 @@
 use bevy::{ecs::system::SystemState, prelude::*};
 
 use crate::model::{
-    components::{Position, TerrainType},
-    resources::CurrentMap,
+    components::{MapLayer, Position},
+    resources::{CurrentMap, DiagonalRule},
     types::MoveDirection,
 };
 
@@ -20,32 +20,104 @@ impl EntityCommand for TryMove {
 
         let mut state: SystemState<(
             ResMut<CurrentMap>,
+            Option<Res<DiagonalRule>>,
             // ResMut<GameLog>,
             Query<&mut Position>,
-            Query<&TerrainType>,
         )> = SystemState::new(world);
 
-        let (current_map, mut q_position, q_terrain_type) = state.get_mut(world);
+        let (mut current_map, diagonal_rule, mut q_position) = state.get_mut(world);
         let Ok(mut position) = q_position.get_mut(entity) else {
             log::error!("Failed to get mut position for entity: {}", entity);
             return;
         };
 
         let new_position = *position + self.0;
-        let Some(terrain_entity) = current_map.get_terrain(new_position) else {
+        if !current_map.in_bounds(new_position) {
             log::error!("Failed to get terrain for entity: {}", entity);
             return;
-        };
+        }
 
-        let Ok(terrain_type) = q_terrain_type.get(terrain_entity) else {
-            log::error!("Failed to get terrain type for entity: {}", entity);
+        let diagonal_rule = diagonal_rule.as_deref().copied().unwrap_or_default();
+        if !diagonal_rule.allows(&current_map, *position, self.0.into()) {
+            log::info!("Cannot move diagonally to {:?}", new_position);
             return;
-        };
+        }
 
-        if *terrain_type == TerrainType::Floor {
-            *position = new_position;
-        } else {
+        if !current_map.is_walkable(new_position) {
             log::info!("Cannot move to {:?}", new_position);
+            return;
+        }
+
+        if let Some(occupant) = current_map.get_actor(new_position).filter(|&e| e != entity) {
+            log::info!("Cannot move to {:?}, occupied by {}", new_position, occupant);
+            return;
+        }
+
+        *position = new_position;
+        if !current_map.entities.move_entity(entity, new_position) {
+            current_map.entities.insert(entity, new_position, MapLayer::Actor);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        components::TerrainType,
+        test_utils::{setup_world, spawn_actor},
+    };
+
+    #[test]
+    fn try_move_moves_occupancy_with_the_actor() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 1));
+
+        TryMove::new(MoveDirection::North).apply(actor, &mut world);
+
+        let map = world.resource::<CurrentMap>();
+        assert_eq!(*world.get::<Position>(actor).unwrap(), Position::new(1, 2));
+        assert_eq!(map.get_actor(Position::new(1, 2)), Some(actor));
+        assert_eq!(map.get_actor(Position::new(1, 1)), None);
+    }
+
+    #[test]
+    fn try_move_into_occupied_tile_does_nothing() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 1));
+        let blocker = spawn_actor(&mut world, Position::new(1, 2));
+
+        TryMove::new(MoveDirection::North).apply(actor, &mut world);
+
+        let map = world.resource::<CurrentMap>();
+        assert_eq!(*world.get::<Position>(actor).unwrap(), Position::new(1, 1));
+        assert_eq!(map.get_actor(Position::new(1, 1)), Some(actor));
+        assert_eq!(map.get_actor(Position::new(1, 2)), Some(blocker));
+    }
+
+    #[test]
+    fn try_move_onto_stairs_and_open_doors_is_allowed() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 1));
+        let mut map = world.resource_mut::<CurrentMap>();
+        map.update_masks(Position::new(1, 2), &TerrainType::DownStairs);
+        map.update_masks(Position::new(1, 3), &TerrainType::OpenDoor);
+
+        TryMove::new(MoveDirection::North).apply(actor, &mut world);
+        assert_eq!(*world.get::<Position>(actor).unwrap(), Position::new(1, 2));
+
+        TryMove::new(MoveDirection::North).apply(actor, &mut world);
+        assert_eq!(*world.get::<Position>(actor).unwrap(), Position::new(1, 3));
+        assert_eq!(world.resource::<CurrentMap>().get_actor(Position::new(1, 3)), Some(actor));
+    }
+
+    #[test]
+    fn try_move_into_closed_door_does_nothing() {
+        let mut world = setup_world();
+        let actor = spawn_actor(&mut world, Position::new(1, 1));
+        world.resource_mut::<CurrentMap>().update_masks(Position::new(1, 2), &TerrainType::Door);
+
+        TryMove::new(MoveDirection::North).apply(actor, &mut world);
+        assert_eq!(*world.get::<Position>(actor).unwrap(), Position::new(1, 1));
+    }
+}
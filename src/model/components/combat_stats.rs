@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+/// Melee strength of an entity. Damage dealt is the attacker's `attack` minus the target's
+/// `defense`.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct CombatStats {
+    pub attack: i32,
+    pub defense: i32,
+}
+
+impl CombatStats {
+    pub fn new(attack: i32, defense: i32) -> Self {
+        Self { attack, defense }
+    }
+}
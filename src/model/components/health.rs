@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+
+/// Hit points of an entity that can be damaged
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: i32,
+    pub max: i32,
+}
+
+impl Health {
+    pub fn new(max: i32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// Subtracts `amount` hit points, returning the damage actually taken
+    pub fn take_damage(&mut self, amount: i32) -> i32 {
+        let amount = amount.clamp(0, self.current.max(0));
+        self.current -= amount;
+        amount
+    }
+
+    /// Restores up to `amount` hit points, returning the amount actually healed
+    pub fn heal(&mut self, amount: i32) -> i32 {
+        let amount = amount.clamp(0, (self.max - self.current).max(0));
+        self.current += amount;
+        amount
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.current >= self.max
+    }
+}
@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// An item lying on the map
+#[derive(Component, Debug)]
+pub struct ItemTag;
@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+/// The layer an entity occupies on the map. Several entities can share a tile, but each tile
+/// usually holds at most one entity on the actor layer.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub enum MapLayer {
+    #[default]
+    Actor,
+    Item,
+    Feature,
+    Effect,
+}
@@ -1,11 +1,19 @@
 mod actor;
 pub use self::actor::*;
+mod combat_stats;
+pub use self::combat_stats::*;
 mod description;
 pub use self::description::*;
 mod fov;
 pub use self::fov::*;
+mod health;
+pub use self::health::*;
 mod input;
 pub use self::input::*;
+mod item;
+pub use self::item::*;
+mod map_layer;
+pub use self::map_layer::*;
 mod position;
 pub use self::position::*;
 mod renderable;
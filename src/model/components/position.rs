@@ -1,6 +1,7 @@
 use std::ops::{Add, AddAssign};
 
 use bevy::prelude::*;
+use brtk::grid::GridCoord;
 
 #[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Deref, DerefMut)]
 #[reflect(Component)]
@@ -32,6 +33,13 @@ impl From<Position> for (i32, i32) {
     }
 }
 
+impl GridCoord for Position {
+    #[inline]
+    fn grid_position(self) -> (i32, i32) {
+        (self.0.x, self.0.y)
+    }
+}
+
 impl Add<Position> for Position {
     type Output = Self;
 
@@ -91,3 +99,22 @@ impl AddAssign<(i32, i32)> for Position {
 //         self.0.y += y;
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use brtk::grid::Grid;
+
+    use super::*;
+
+    #[test]
+    fn positions_index_grids() {
+        let mut grid = Grid::new_fn((4, 3), |index, _| index);
+        assert_eq!(grid[Position::new(2, 1)], 6);
+        assert_eq!(grid[Position::new(2, 1)], grid[(2, 1)]);
+
+        grid[Position::new(3, 2)] = 100;
+        assert_eq!(grid.get(Position::new(3, 2)), Some(&100));
+        assert_eq!(grid.get(Position::new(4, 0)), None);
+        assert_eq!(grid.get(Position::new(0, -1)), None);
+    }
+}
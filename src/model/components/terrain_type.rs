@@ -10,6 +10,7 @@ pub enum TerrainType {
     Floor,
     Wall,
     Door,
+    OpenDoor,
     UpStairs,
     DownStairs,
 }
@@ -23,4 +24,39 @@ impl TerrainType {
             _ => false,
         }
     }
+
+    /// Returns true if this terrain type stops actors from walking onto it
+    pub fn blocks_movement(&self) -> bool {
+        matches!(self, TerrainType::Wall | TerrainType::Door)
+    }
+
+    /// Returns true if nothing can ever get through this terrain type, not even by opening it
+    pub fn is_impassable(&self) -> bool {
+        matches!(self, TerrainType::Wall)
+    }
+
+    /// Returns the character used for this terrain type in text maps and logs
+    pub fn glyph(&self) -> char {
+        match self {
+            TerrainType::Floor => '.',
+            TerrainType::Wall => '#',
+            TerrainType::Door => '+',
+            TerrainType::OpenDoor => '\'',
+            TerrainType::UpStairs => '<',
+            TerrainType::DownStairs => '>',
+        }
+    }
+
+    /// Returns the terrain type for a character of a text map, if any
+    pub fn from_glyph(glyph: char) -> Option<Self> {
+        match glyph {
+            '.' => Some(TerrainType::Floor),
+            '#' => Some(TerrainType::Wall),
+            '+' => Some(TerrainType::Door),
+            '\'' => Some(TerrainType::OpenDoor),
+            '<' => Some(TerrainType::UpStairs),
+            '>' => Some(TerrainType::DownStairs),
+            _ => None,
+        }
+    }
 }
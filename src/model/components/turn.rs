@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use std::collections::VecDeque;
 
-use crate::model::types::GameAction;
+use crate::model::{types::GameAction, ModelConstants};
 
 // Define components for the turn system
 #[derive(Component, Debug)]
@@ -38,6 +38,11 @@ impl TurnActor {
     pub fn is_alive(&self) -> bool {
         self.alive
     }
+
+    /// Time an action with the given base cost takes for this actor. Faster actors spend less time.
+    pub fn action_cost(&self, base_cost: u64) -> u64 {
+        base_cost * ModelConstants::NORMAL_SPEED / self.speed.max(1)
+    }
 }
 
 #[derive(Component)]
@@ -0,0 +1,183 @@
+use brtk::grid::{Grid, GridRect};
+
+use crate::model::{components::TerrainType, generation::DungeonGenerator, ModelConstants};
+
+use super::Room;
+
+/// Generates dungeons by binary space partitioning.
+///
+/// The map is split in two recursively, a room is placed in every leaf, and the rooms of sibling
+/// subtrees are connected on the way back up. Compared to `DungeonGenerator`, the rooms are spread
+/// evenly over the whole map.
+pub struct BspGenerator {
+    pub width: usize,
+    pub height: usize,
+    /// Smallest width or height of a partition. Partitions are not split below twice this size.
+    pub min_leaf_size: i32,
+    /// Maximum number of times the map is split
+    pub max_depth: u32,
+    pub min_room_size: i32,
+    pub rooms: Vec<Room>,
+}
+
+impl Default for BspGenerator {
+    fn default() -> Self {
+        Self {
+            width: ModelConstants::MAP_WIDTH,
+            height: ModelConstants::MAP_HEIGHT,
+            min_leaf_size: 7,
+            max_depth: 5,
+            min_room_size: 4,
+            rooms: Vec::new(),
+        }
+    }
+}
+
+impl BspGenerator {
+    /// Create a new BSP generator with the specified dimensions and default settings
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, ..Default::default() }
+    }
+
+    /// Generate a complete dungeon map with rooms, corridors, doors, and stairs
+    ///
+    /// Returns a Grid<TerrainType> representing the completed dungeon
+    pub fn generate(&mut self, rng: &mut fastrand::Rng) -> Grid<TerrainType> {
+        let mut grid = Grid::new_fill((self.width, self.height), TerrainType::Wall);
+        self.layout(&mut grid, rng);
+
+        DungeonGenerator::place_doors(&mut grid, &self.rooms, rng);
+        DungeonGenerator::place_stairs(&mut grid, &self.rooms, rng);
+        DungeonGenerator::connect_unreachable_regions(&mut grid);
+
+        grid
+    }
+
+    /// Partition `grid` and carve the rooms and the corridors connecting them
+    pub fn layout(&mut self, grid: &mut Grid<TerrainType>, rng: &mut fastrand::Rng) {
+        self.rooms.clear();
+
+        // Keep the outer edge of the map solid
+        let bounds = GridRect::new(1, 1, grid.width() as i32 - 2, grid.height() as i32 - 2);
+        self.partition(grid, bounds, 0, rng);
+    }
+
+    /// Split `rect` in two, or place a room in it if it is a leaf.
+    ///
+    /// Returns a floor position of the subtree, used to connect it to its sibling.
+    fn partition(
+        &mut self,
+        grid: &mut Grid<TerrainType>,
+        rect: GridRect,
+        depth: u32,
+        rng: &mut fastrand::Rng,
+    ) -> Option<(i32, i32)> {
+        let Some((first, second)) = self.split(rect, depth, rng) else {
+            return self.place_room(grid, rect, rng);
+        };
+
+        let first_center = self.partition(grid, first, depth + 1, rng);
+        let second_center = self.partition(grid, second, depth + 1, rng);
+
+        match (first_center, second_center) {
+            (Some(from), Some(to)) => {
+                DungeonGenerator::carve_corridor(grid, from, to);
+                Some(if rng.bool() { from } else { to })
+            }
+            (center, None) | (None, center) => center,
+        }
+    }
+
+    /// Split a rect across its longer side, or return None if it is a leaf
+    fn split(&self, rect: GridRect, depth: u32, rng: &mut fastrand::Rng) -> Option<(GridRect, GridRect)> {
+        if depth >= self.max_depth {
+            return None;
+        }
+
+        let can_split_width = rect.width >= self.min_leaf_size * 2;
+        let can_split_height = rect.height >= self.min_leaf_size * 2;
+
+        // Prefer splitting across the longer side to avoid long, thin partitions
+        let split_width = match (can_split_width, can_split_height) {
+            (false, false) => return None,
+            (true, false) => true,
+            (false, true) => false,
+            (true, true) if rect.width * 4 > rect.height * 5 => true,
+            (true, true) if rect.height * 4 > rect.width * 5 => false,
+            (true, true) => rng.bool(),
+        };
+
+        if split_width {
+            let split = rng.i32(self.min_leaf_size..=rect.width - self.min_leaf_size);
+            Some((
+                GridRect::new(rect.x, rect.y, split, rect.height),
+                GridRect::new(rect.x + split, rect.y, rect.width - split, rect.height),
+            ))
+        } else {
+            let split = rng.i32(self.min_leaf_size..=rect.height - self.min_leaf_size);
+            Some((
+                GridRect::new(rect.x, rect.y, rect.width, split),
+                GridRect::new(rect.x, rect.y + split, rect.width, rect.height - split),
+            ))
+        }
+    }
+
+    /// Place a random room inside a leaf, leaving a wall between it and the next leaf
+    fn place_room(
+        &mut self,
+        grid: &mut Grid<TerrainType>,
+        leaf: GridRect,
+        rng: &mut fastrand::Rng,
+    ) -> Option<(i32, i32)> {
+        let (max_width, max_height) = (leaf.width - 1, leaf.height - 1);
+        if max_width < self.min_room_size || max_height < self.min_room_size {
+            return None;
+        }
+
+        let width = rng.i32(self.min_room_size..=max_width);
+        let height = rng.i32(self.min_room_size..=max_height);
+        let x = leaf.x + rng.i32(0..=max_width - width);
+        let y = leaf.y + rng.i32(0..=max_height - height);
+
+        let room = Room::new(x, y, width, height);
+        DungeonGenerator::carve_room(grid, &room);
+        self.rooms.push(room);
+
+        Some(room.center())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_levels_are_valid() {
+        for seed in 0..100 {
+            let mut rng = fastrand::Rng::with_seed(seed);
+            let mut generator = BspGenerator::new(60, 40);
+            let grid = generator.generate(&mut rng);
+
+            assert!(!generator.rooms.is_empty());
+            for (index, room) in generator.rooms.iter().enumerate() {
+                assert!(room.x >= 1 && room.y >= 1, "seed {seed} placed a room on the edge");
+                assert!(
+                    room.x + room.width < 60 && room.y + room.height < 40,
+                    "seed {seed} placed a room out of bounds"
+                );
+
+                for other in &generator.rooms[index + 1..] {
+                    let overlaps = room.positions().any(|position| other.contains(position));
+                    assert!(!overlaps, "seed {seed} placed overlapping rooms");
+                }
+            }
+
+            assert!(DungeonGenerator::find_terrain(&grid, TerrainType::UpStairs).is_some());
+            assert!(DungeonGenerator::find_terrain(&grid, TerrainType::DownStairs).is_some());
+            assert!(
+                DungeonGenerator::unreachable_positions(&grid).is_empty(),
+                "seed {seed} left walkable tiles unreachable"
+            );
+        }
+    }
+}
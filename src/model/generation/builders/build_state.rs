@@ -0,0 +1,48 @@
+use brtk::grid::Grid;
+
+use crate::model::{
+    components::TerrainType,
+    generation::{Room, Spawn},
+};
+
+/// A copy of the level taken after one step of a `BuilderChain`
+#[derive(Clone)]
+pub struct Snapshot {
+    /// Name of the step that produced this layout
+    pub step: &'static str,
+    pub grid: Grid<TerrainType>,
+}
+
+/// The level being built, passed from one step of a `BuilderChain` to the next
+#[derive(Clone)]
+pub struct BuildState {
+    pub grid: Grid<TerrainType>,
+    /// Rooms carved so far, empty for layouts without rooms
+    pub rooms: Vec<Room>,
+    /// Monsters and items placed so far
+    pub spawns: Vec<Spawn>,
+    /// Layouts recorded after each step, oldest first
+    pub snapshots: Vec<Snapshot>,
+    /// Whether `take_snapshot` records anything
+    pub record_snapshots: bool,
+}
+
+impl BuildState {
+    /// Create a level of solid walls
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            grid: Grid::new_fill((width, height), TerrainType::Wall),
+            rooms: Vec::new(),
+            spawns: Vec::new(),
+            snapshots: Vec::new(),
+            record_snapshots: false,
+        }
+    }
+
+    /// Record the current layout, if snapshots are enabled
+    pub fn take_snapshot(&mut self, step: &'static str) {
+        if self.record_snapshots {
+            self.snapshots.push(Snapshot { step, grid: self.grid.clone() });
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use brtk::grid::Grid;
+
+use crate::model::{
+    components::TerrainType,
+    generation::{MapGenerator, Room, Spawn},
+};
+
+use super::{BuildState, Snapshot};
+
+/// The first step of a `BuilderChain`, laying out the level from solid walls
+pub trait InitialBuilder: Send + Sync {
+    /// Name of the step, used for snapshots and logging
+    fn name(&self) -> &'static str;
+
+    fn build(&mut self, state: &mut BuildState, rng: &mut fastrand::Rng);
+}
+
+/// A later step of a `BuilderChain`, transforming the level laid out so far
+pub trait MetaBuilder: Send + Sync {
+    /// Name of the step, used for snapshots and logging
+    fn name(&self) -> &'static str;
+
+    fn build(&mut self, state: &mut BuildState, rng: &mut fastrand::Rng);
+}
+
+/// Generates levels by running an initial builder followed by meta builders, in order.
+///
+/// ```ignore
+/// let mut chain = BuilderChain::new(80, 50, DungeonGenerator::default())
+///     .with(RoomCullBuilder::new(16))
+///     .with(CorridorBuilder::new(CorridorStyle::NearestNeighbor))
+///     .with(DoorBuilder)
+///     .with(CullUnreachableBuilder)
+///     .with(StairsBuilder)
+///     .with(VaultBuilder::new(Vault::defaults(), 2));
+/// ```
+///
+/// The chain only runs the steps it is given, so chains used as a `MapGenerator` should end with
+/// a `StairsBuilder`.
+pub struct BuilderChain {
+    width: usize,
+    height: usize,
+    initial: Box<dyn InitialBuilder>,
+    meta: Vec<Box<dyn MetaBuilder>>,
+    record_snapshots: bool,
+    rooms: Vec<Room>,
+    spawns: Vec<Spawn>,
+    snapshots: Vec<Snapshot>,
+}
+
+impl BuilderChain {
+    /// Create a chain of the specified dimensions, starting with `initial`
+    pub fn new(width: usize, height: usize, initial: impl InitialBuilder + 'static) -> Self {
+        Self {
+            width,
+            height,
+            initial: Box::new(initial),
+            meta: Vec::new(),
+            record_snapshots: false,
+            rooms: Vec::new(),
+            spawns: Vec::new(),
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Append a step to the chain
+    pub fn with(mut self, builder: impl MetaBuilder + 'static) -> Self {
+        self.meta.push(Box::new(builder));
+        self
+    }
+
+    /// Record a snapshot of the level after every step
+    pub fn with_snapshots(mut self, record_snapshots: bool) -> Self {
+        self.record_snapshots = record_snapshots;
+        self
+    }
+
+    /// Run every step of the chain, returning the final state
+    pub fn build(&mut self, rng: &mut fastrand::Rng) -> BuildState {
+        let mut state = BuildState::new(self.width, self.height);
+        state.record_snapshots = self.record_snapshots;
+
+        self.initial.build(&mut state, rng);
+        Self::finish_step(&mut state, self.initial.name());
+
+        for builder in &mut self.meta {
+            builder.build(&mut state, rng);
+            Self::finish_step(&mut state, builder.name());
+        }
+
+        state
+    }
+
+    /// Snapshots of the last generated level, empty unless enabled with `with_snapshots`
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    fn finish_step(state: &mut BuildState, step: &'static str) {
+        log::debug!("Builder step {} done with {} rooms", step, state.rooms.len());
+        if state.record_snapshots {
+            log::trace!("Layout after {}:\n{}", step, state.grid.display_with(TerrainType::glyph));
+        }
+
+        state.take_snapshot(step);
+    }
+}
+
+impl MapGenerator for BuilderChain {
+    fn generate(&mut self, rng: &mut fastrand::Rng) -> Grid<TerrainType> {
+        let state = self.build(rng);
+        self.rooms = state.rooms;
+        self.spawns = state.spawns;
+        self.snapshots = state.snapshots;
+        state.grid
+    }
+
+    fn rooms(&self) -> &[Room] {
+        &self.rooms
+    }
+
+    fn spawns(&self) -> &[Spawn] {
+        &self.spawns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::generation::{
+        builders::{CorridorBuilder, CorridorStyle, DoorBuilder, StairsBuilder},
+        DungeonGenerator,
+    };
+
+    fn chain() -> BuilderChain {
+        BuilderChain::new(40, 30, DungeonGenerator::new(40, 30))
+            .with(CorridorBuilder::new(CorridorStyle::Dogleg))
+            .with(DoorBuilder)
+            .with(StairsBuilder)
+    }
+
+    fn glyphs(grid: &Grid<TerrainType>) -> String {
+        grid.to_text(TerrainType::glyph)
+    }
+
+    #[test]
+    fn snapshots_are_recorded_after_every_step_in_order() {
+        let mut chain = chain().with_snapshots(true);
+        let grid = chain.generate(&mut fastrand::Rng::with_seed(11));
+
+        let steps: Vec<_> = chain.snapshots().iter().map(|snapshot| snapshot.step).collect();
+        assert_eq!(steps, ["rooms", "dogleg corridors", "doors", "stairs"]);
+
+        // Stairs only appear in the last step, which is the generated level
+        let has_stairs = |grid: &Grid<TerrainType>| {
+            DungeonGenerator::find_terrain(grid, TerrainType::UpStairs).is_some()
+        };
+        let snapshots = chain.snapshots();
+        assert!(snapshots[..3].iter().all(|snapshot| !has_stairs(&snapshot.grid)));
+        assert_eq!(glyphs(&snapshots[3].grid), glyphs(&grid));
+
+        // Every step changes the layout
+        for pair in snapshots.windows(2) {
+            assert_ne!(glyphs(&pair[0].grid), glyphs(&pair[1].grid), "{} changed nothing", pair[1].step);
+        }
+    }
+
+    #[test]
+    fn snapshots_are_off_by_default() {
+        let mut chain = chain();
+        chain.generate(&mut fastrand::Rng::with_seed(11));
+
+        assert!(chain.snapshots().is_empty());
+    }
+}
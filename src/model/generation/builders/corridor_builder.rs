@@ -0,0 +1,125 @@
+use brtk::grid::{line, Grid};
+
+use crate::model::{
+    components::TerrainType,
+    generation::{DungeonGenerator, Room},
+};
+
+use super::{BuildState, MetaBuilder};
+
+/// How a `CorridorBuilder` connects rooms
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CorridorStyle {
+    /// L-shaped corridors chaining the rooms from the top left to the bottom right
+    #[default]
+    Dogleg,
+    /// L-shaped corridors from every room to the closest room already connected, which gives
+    /// shorter corridors branching like a tree
+    NearestNeighbor,
+    /// Straight diagonal corridors chaining the rooms from the top left to the bottom right
+    Straight,
+}
+
+impl CorridorStyle {
+    /// Carve corridors connecting every room
+    pub fn connect(self, grid: &mut Grid<TerrainType>, rooms: &[Room]) {
+        if rooms.len() < 2 {
+            return;
+        }
+
+        match self {
+            CorridorStyle::Dogleg => {
+                for (from, to) in Self::chain(rooms) {
+                    DungeonGenerator::carve_corridor(grid, from, to);
+                }
+            }
+            CorridorStyle::NearestNeighbor => {
+                for (from, to) in Self::nearest_neighbors(rooms) {
+                    DungeonGenerator::carve_corridor(grid, from, to);
+                }
+            }
+            CorridorStyle::Straight => {
+                for (from, to) in Self::chain(rooms) {
+                    Self::carve_straight_corridor(grid, from, to);
+                }
+            }
+        }
+    }
+
+    /// Pairs of room centers, sorted for consistent corridor generation
+    fn chain(rooms: &[Room]) -> Vec<((i32, i32), (i32, i32))> {
+        let mut centers: Vec<(i32, i32)> = rooms.iter().map(Room::center).collect();
+        centers.sort_by_key(|center| center.0 + center.1);
+        centers.windows(2).map(|pair| (pair[0], pair[1])).collect()
+    }
+
+    /// Pairs of room centers, joining every room to the closest room connected before it
+    fn nearest_neighbors(rooms: &[Room]) -> Vec<((i32, i32), (i32, i32))> {
+        let mut connected = vec![rooms[0].center()];
+        let mut remaining: Vec<(i32, i32)> = rooms[1..].iter().map(Room::center).collect();
+        let mut pairs = Vec::with_capacity(remaining.len());
+
+        let distance = |a: (i32, i32), b: (i32, i32)| (a.0 - b.0).abs() + (a.1 - b.1).abs();
+
+        while !remaining.is_empty() {
+            let Some((index, from, to)) = remaining
+                .iter()
+                .enumerate()
+                .flat_map(|(index, &to)| connected.iter().map(move |&from| (index, from, to)))
+                .min_by_key(|&(_, from, to)| distance(from, to))
+            else {
+                break;
+            };
+
+            pairs.push((from, to));
+            connected.push(remaining.swap_remove(index));
+        }
+
+        pairs
+    }
+
+    /// Carve a straight line, widening diagonal steps so they can be walked without cutting corners
+    fn carve_straight_corridor(grid: &mut Grid<TerrainType>, from: (i32, i32), to: (i32, i32)) {
+        let mut previous = from;
+        for position in line(from, to) {
+            if position.0 != previous.0 && position.1 != previous.1 {
+                Self::carve_wall(grid, (position.0, previous.1));
+            }
+
+            Self::carve_wall(grid, position);
+            previous = position;
+        }
+    }
+
+    fn carve_wall(grid: &mut Grid<TerrainType>, position: (i32, i32)) {
+        if grid.get(position) == Some(&TerrainType::Wall) {
+            grid[position] = TerrainType::Floor;
+        }
+    }
+}
+
+/// Connects the rooms of the level with corridors
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CorridorBuilder {
+    pub style: CorridorStyle,
+}
+
+impl CorridorBuilder {
+    pub fn new(style: CorridorStyle) -> Self {
+        Self { style }
+    }
+}
+
+impl MetaBuilder for CorridorBuilder {
+    fn name(&self) -> &'static str {
+        match self.style {
+            CorridorStyle::Dogleg => "dogleg corridors",
+            CorridorStyle::NearestNeighbor => "nearest neighbor corridors",
+            CorridorStyle::Straight => "straight corridors",
+        }
+    }
+
+    fn build(&mut self, state: &mut BuildState, _rng: &mut fastrand::Rng) {
+        self.style.connect(&mut state.grid, &state.rooms);
+    }
+}
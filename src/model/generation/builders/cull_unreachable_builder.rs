@@ -0,0 +1,93 @@
+use brtk::{grid::Connectivity, region::label_regions};
+
+use crate::model::{components::TerrainType, generation::DungeonGenerator};
+
+use super::{BuildState, MetaBuilder};
+
+/// Fills every area that cannot be walked to with walls.
+///
+/// The area holding the up stairs is kept, or the largest area if there are no stairs yet. Run it
+/// before the `StairsBuilder`, or the down stairs may be culled along with their area. Rooms left
+/// without any floor are forgotten.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CullUnreachableBuilder;
+
+impl MetaBuilder for CullUnreachableBuilder {
+    fn name(&self) -> &'static str {
+        "cull unreachable"
+    }
+
+    fn build(&mut self, state: &mut BuildState, _rng: &mut fastrand::Rng) {
+        let grid = &mut state.grid;
+        let regions = label_regions(grid, Connectivity::Four, |_, terrain| !terrain.is_impassable());
+        let kept = match DungeonGenerator::find_terrain(grid, TerrainType::UpStairs) {
+            Some(up_stairs) => regions.region_at(up_stairs),
+            None => regions.largest(),
+        };
+        let Some(kept) = kept.map(|region| region.id) else {
+            return;
+        };
+
+        for region in regions.regions.iter().filter(|region| region.id != kept) {
+            log::debug!("Culling region of {} tiles at {:?}", region.size(), region.bounds);
+            for &position in &region.cells {
+                grid[position] = TerrainType::Wall;
+            }
+        }
+
+        state.rooms.retain(|room| room.positions().any(|position| !grid[position].is_impassable()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        generation::Room,
+        test_utils::{level, level_text},
+    };
+
+    fn state(text: &str, rooms: &[Room]) -> BuildState {
+        let mut state = BuildState::new(1, 1);
+        state.grid = level(text);
+        state.rooms = rooms.to_vec();
+        state
+    }
+
+    fn cull(state: &mut BuildState) -> String {
+        CullUnreachableBuilder.build(state, &mut fastrand::Rng::with_seed(1));
+        level_text(&state.grid)
+    }
+
+    #[test]
+    fn the_area_holding_the_up_stairs_is_kept() {
+        let mut state = state(
+            "
+##########
+#..#.....#
+#.<#.....#
+##########",
+            &[Room::new(1, 1, 2, 2), Room::new(4, 1, 5, 2)],
+        );
+
+        assert_eq!(cull(&mut state), "##########\n#..#######\n#.<#######\n##########");
+        assert_eq!(state.rooms, [Room::new(1, 1, 2, 2)]);
+    }
+
+    #[test]
+    fn the_largest_area_is_kept_without_stairs() {
+        let mut state = state(
+            "
+##########
+#..#.....#
+#..#..#..#
+########.#
+#.+......#
+##########",
+            &[],
+        );
+
+        let expected = "##########\n####.....#\n####..#..#\n########.#\n#.+......#\n##########";
+        assert_eq!(cull(&mut state), expected);
+    }
+}
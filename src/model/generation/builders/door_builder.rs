@@ -0,0 +1,17 @@
+use crate::model::generation::DungeonGenerator;
+
+use super::{BuildState, MetaBuilder};
+
+/// Places doors where corridors enter rooms
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DoorBuilder;
+
+impl MetaBuilder for DoorBuilder {
+    fn name(&self) -> &'static str {
+        "doors"
+    }
+
+    fn build(&mut self, state: &mut BuildState, rng: &mut fastrand::Rng) {
+        DungeonGenerator::place_doors(&mut state.grid, &state.rooms, rng);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::model::generation::{BspGenerator, CaveGenerator, DrunkardGenerator, DungeonGenerator};
+
+use super::{BuildState, InitialBuilder};
+
+/// Scatters unconnected rooms, leaving corridors to a `CorridorBuilder`
+impl InitialBuilder for DungeonGenerator {
+    fn name(&self) -> &'static str {
+        "rooms"
+    }
+
+    fn build(&mut self, state: &mut BuildState, rng: &mut fastrand::Rng) {
+        (self.width, self.height) = (state.grid.width(), state.grid.height());
+        self.layout(&mut state.grid, rng);
+        state.rooms.clone_from(&self.rooms);
+    }
+}
+
+/// Partitions the level into rooms, already connected by corridors
+impl InitialBuilder for BspGenerator {
+    fn name(&self) -> &'static str {
+        "bsp"
+    }
+
+    fn build(&mut self, state: &mut BuildState, rng: &mut fastrand::Rng) {
+        self.layout(&mut state.grid, rng);
+        state.rooms.clone_from(&self.rooms);
+    }
+}
+
+/// Grows caves, which may still hold pockets for a `CullUnreachableBuilder`
+impl InitialBuilder for CaveGenerator {
+    fn name(&self) -> &'static str {
+        "caves"
+    }
+
+    fn build(&mut self, state: &mut BuildState, rng: &mut fastrand::Rng) {
+        (self.width, self.height) = (state.grid.width(), state.grid.height());
+        state.grid = self.layout(rng);
+    }
+}
+
+/// Carves connected tunnels with random walkers
+impl InitialBuilder for DrunkardGenerator {
+    fn name(&self) -> &'static str {
+        "drunkard"
+    }
+
+    fn build(&mut self, state: &mut BuildState, rng: &mut fastrand::Rng) {
+        self.layout(&mut state.grid, rng);
+    }
+}
@@ -0,0 +1,20 @@
+mod build_state;
+mod builder_chain;
+mod corridor_builder;
+mod cull_unreachable_builder;
+mod door_builder;
+mod initial_builders;
+mod prefab_builder;
+mod room_cull_builder;
+mod stairs_builder;
+mod vault_builder;
+
+pub use build_state::{BuildState, Snapshot};
+pub use builder_chain::{BuilderChain, InitialBuilder, MetaBuilder};
+pub use corridor_builder::{CorridorBuilder, CorridorStyle};
+pub use cull_unreachable_builder::CullUnreachableBuilder;
+pub use door_builder::DoorBuilder;
+pub use prefab_builder::PrefabBuilder;
+pub use room_cull_builder::RoomCullBuilder;
+pub use stairs_builder::StairsBuilder;
+pub use vault_builder::VaultBuilder;
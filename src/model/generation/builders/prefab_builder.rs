@@ -0,0 +1,61 @@
+use brtk::grid::{Grid, GridParseError};
+
+use crate::model::{components::TerrainType, generation::Room};
+
+use super::{BuildState, MetaBuilder};
+
+/// Stamps a handcrafted layout into a random room large enough to hold it.
+///
+/// The prefab is kept a tile away from the room walls so the room's entrances stay connected.
+/// Rooms that are too small are left alone, as are layouts without rooms.
+#[derive(Clone)]
+pub struct PrefabBuilder {
+    /// Terrain of each prefab cell, or None to keep the terrain underneath
+    pub template: Grid<Option<TerrainType>>,
+}
+
+impl PrefabBuilder {
+    pub fn new(template: Grid<Option<TerrainType>>) -> Self {
+        Self { template }
+    }
+
+    /// Parse a prefab drawn with terrain glyphs, where spaces keep the terrain underneath
+    pub fn from_text(text: &str) -> Result<Self, GridParseError> {
+        let template = Grid::from_text(text, |glyph| match glyph {
+            ' ' => Some(None),
+            glyph => TerrainType::from_glyph(glyph).map(Some),
+        })?;
+
+        Ok(Self::new(template))
+    }
+
+    /// Whether `room` holds the prefab with a tile to spare on every side
+    fn fits(&self, room: &Room) -> bool {
+        room.width >= self.template.width() as i32 + 2 && room.height >= self.template.height() as i32 + 2
+    }
+}
+
+impl MetaBuilder for PrefabBuilder {
+    fn name(&self) -> &'static str {
+        "prefab"
+    }
+
+    fn build(&mut self, state: &mut BuildState, rng: &mut fastrand::Rng) {
+        let candidates: Vec<&Room> = state.rooms.iter().filter(|room| self.fits(room)).collect();
+        if candidates.is_empty() {
+            log::debug!("No room can hold a {}x{} prefab", self.template.width(), self.template.height());
+            return;
+        }
+
+        let room = candidates[rng.usize(0..candidates.len())];
+        let (width, height) = (self.template.width() as i32, self.template.height() as i32);
+        let x = room.x + 1 + rng.i32(0..=room.width - width - 2);
+        let y = room.y + 1 + rng.i32(0..=room.height - height - 2);
+
+        for ((local_x, local_y), terrain) in self.template.iter_with_positions() {
+            if let Some(terrain) = terrain {
+                state.grid[(x + local_x, y + local_y)] = terrain.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::model::{components::TerrainType, generation::Room};
+
+use super::{BuildState, MetaBuilder};
+
+/// Removes rooms that are too small, and random rooms beyond a maximum count, filling them back
+/// in with walls.
+///
+/// Run it before corridors are carved, as a culled room leaves a gap in any corridor through it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RoomCullBuilder {
+    /// Rooms with a smaller area are removed
+    pub min_area: i32,
+    /// Most rooms kept
+    pub max_rooms: Option<usize>,
+}
+
+impl RoomCullBuilder {
+    pub fn new(min_area: i32) -> Self {
+        Self { min_area, max_rooms: None }
+    }
+
+    pub fn with_max_rooms(mut self, max_rooms: usize) -> Self {
+        self.max_rooms = Some(max_rooms);
+        self
+    }
+}
+
+impl MetaBuilder for RoomCullBuilder {
+    fn name(&self) -> &'static str {
+        "room culling"
+    }
+
+    fn build(&mut self, state: &mut BuildState, rng: &mut fastrand::Rng) {
+        let (mut kept, mut culled): (Vec<Room>, Vec<Room>) =
+            state.rooms.iter().partition(|room| room.width * room.height >= self.min_area);
+
+        if let Some(max_rooms) = self.max_rooms {
+            while kept.len() > max_rooms {
+                culled.push(kept.swap_remove(rng.usize(0..kept.len())));
+            }
+        }
+
+        for room in &culled {
+            for position in room.positions() {
+                if let Some(terrain) = state.grid.get_mut(position) {
+                    *terrain = TerrainType::Wall;
+                }
+            }
+        }
+
+        log::debug!("Culled {} rooms, {} left", culled.len(), kept.len());
+        state.rooms = kept;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{generation::DungeonGenerator, test_utils::level_text};
+
+    fn state(rooms: &[Room]) -> BuildState {
+        let mut state = BuildState::new(16, 7);
+        for room in rooms {
+            DungeonGenerator::carve_room(&mut state.grid, room);
+        }
+        state.rooms = rooms.to_vec();
+        state
+    }
+
+    #[test]
+    fn small_rooms_are_filled_in() {
+        let mut state = state(&[Room::new(1, 1, 2, 2), Room::new(4, 1, 5, 5), Room::new(10, 1, 4, 4)]);
+        RoomCullBuilder::new(9).build(&mut state, &mut fastrand::Rng::with_seed(1));
+
+        let expected = "\
+################
+####.....#######
+####.....#....##
+####.....#....##
+####.....#....##
+####.....#....##
+################";
+        assert_eq!(level_text(&state.grid), expected);
+        assert_eq!(state.rooms, [Room::new(4, 1, 5, 5), Room::new(10, 1, 4, 4)]);
+    }
+
+    #[test]
+    fn rooms_beyond_the_maximum_are_filled_in() {
+        let rooms = [Room::new(1, 1, 3, 3), Room::new(5, 1, 3, 3), Room::new(9, 1, 3, 3)];
+        let mut state = state(&rooms);
+        RoomCullBuilder::new(0).with_max_rooms(1).build(&mut state, &mut fastrand::Rng::with_seed(1));
+
+        assert_eq!(state.rooms.len(), 1);
+        let kept = state.rooms[0];
+        for room in &rooms {
+            let is_floor = room.positions().all(|position| state.grid[position] == TerrainType::Floor);
+            assert_eq!(is_floor, *room == kept);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::model::{components::TerrainType, generation::DungeonGenerator};
+
+use super::{BuildState, MetaBuilder};
+
+/// Places the up and down stairs, in distant rooms when the level has at least two of them and
+/// otherwise on the floor tiles farthest apart
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StairsBuilder;
+
+impl MetaBuilder for StairsBuilder {
+    fn name(&self) -> &'static str {
+        "stairs"
+    }
+
+    fn build(&mut self, state: &mut BuildState, rng: &mut fastrand::Rng) {
+        let grid = &mut state.grid;
+        DungeonGenerator::place_stairs(grid, &state.rooms, rng);
+
+        let has_up_stairs = DungeonGenerator::find_terrain(grid, TerrainType::UpStairs).is_some();
+        let has_down_stairs = DungeonGenerator::find_terrain(grid, TerrainType::DownStairs).is_some();
+        if has_up_stairs && has_down_stairs {
+            return;
+        }
+
+        // Rooms too small for stairs, or no rooms at all
+        for terrain in grid.iter_mut() {
+            if matches!(terrain, TerrainType::UpStairs | TerrainType::DownStairs) {
+                *terrain = TerrainType::Floor;
+            }
+        }
+        DungeonGenerator::place_stairs_far_apart(grid, rng);
+    }
+}
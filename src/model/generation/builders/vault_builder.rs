@@ -0,0 +1,47 @@
+use crate::model::generation::Vault;
+
+use super::{BuildState, MetaBuilder};
+
+/// Places random vaults where they fit without cutting anything off or overlapping each other,
+/// recording their spawns.
+///
+/// Run it after the `StairsBuilder`, so vaults keep clear of the stairs and stay reachable from
+/// the up stairs.
+#[derive(Clone)]
+pub struct VaultBuilder {
+    pub vaults: Vec<Vault>,
+    /// Most vaults placed, each picked once
+    pub count: usize,
+    /// Spots tried for each vault before giving up on it
+    pub max_attempts: usize,
+}
+
+impl VaultBuilder {
+    pub fn new(vaults: Vec<Vault>, count: usize) -> Self {
+        Self { vaults, count, max_attempts: 50 }
+    }
+}
+
+impl MetaBuilder for VaultBuilder {
+    fn name(&self) -> &'static str {
+        "vaults"
+    }
+
+    fn build(&mut self, state: &mut BuildState, rng: &mut fastrand::Rng) {
+        let mut vaults: Vec<&Vault> = self.vaults.iter().collect();
+        rng.shuffle(&mut vaults);
+
+        // Vaults never overlap, so one cannot wall in what another spawns
+        let mut placed = Vec::with_capacity(self.count);
+        for vault in vaults {
+            if placed.len() == self.count {
+                break;
+            }
+
+            if let Some((placement, spawns)) = vault.place(&mut state.grid, &placed, rng, self.max_attempts) {
+                placed.push(placement.bounds());
+                state.spawns.extend(spawns);
+            }
+        }
+    }
+}
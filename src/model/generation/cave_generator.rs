@@ -0,0 +1,132 @@
+use brtk::{
+    grid::{Connectivity, Grid},
+    region::label_regions,
+};
+
+use crate::model::{components::TerrainType, generation::DungeonGenerator, ModelConstants};
+
+/// Generates natural caves with a cellular automaton.
+///
+/// The map starts as random noise, and each smoothing pass turns a cell into wall or floor
+/// depending on how many of its 8 neighbors are walls. Pockets cut off from the largest cave are
+/// filled in, and the stairs are placed as far apart as the cave allows.
+pub struct CaveGenerator {
+    pub width: usize,
+    pub height: usize,
+    /// Chance for each cell to start as a wall
+    pub fill_probability: f32,
+    pub smoothing_passes: u32,
+    /// A floor cell becomes a wall with at least this many wall neighbors
+    pub birth_limit: usize,
+    /// A wall cell stays a wall with at least this many wall neighbors
+    pub survival_limit: usize,
+}
+
+impl Default for CaveGenerator {
+    fn default() -> Self {
+        Self {
+            width: ModelConstants::MAP_WIDTH,
+            height: ModelConstants::MAP_HEIGHT,
+            fill_probability: 0.45,
+            smoothing_passes: 5,
+            birth_limit: 5,
+            survival_limit: 4,
+        }
+    }
+}
+
+impl CaveGenerator {
+    /// Create a new cave generator with the specified dimensions and default settings
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, ..Default::default() }
+    }
+
+    /// Generate a cave level with up and down stairs
+    ///
+    /// Returns a Grid<TerrainType> representing the completed cave
+    pub fn generate(&self, rng: &mut fastrand::Rng) -> Grid<TerrainType> {
+        let mut grid = self.layout(rng);
+        Self::keep_largest_region(&mut grid);
+        DungeonGenerator::place_stairs_far_apart(&mut grid, rng);
+
+        grid
+    }
+
+    /// Grow the raw caves, which may still hold pockets cut off from each other
+    pub fn layout(&self, rng: &mut fastrand::Rng) -> Grid<TerrainType> {
+        let mut walls = self.random_fill(rng);
+
+        for _ in 0..self.smoothing_passes {
+            walls = self.smooth(&walls);
+        }
+
+        walls.map(|&wall| if wall { TerrainType::Wall } else { TerrainType::Floor })
+    }
+
+    /// Fill the map with random walls, keeping its edges solid
+    fn random_fill(&self, rng: &mut fastrand::Rng) -> Grid<bool> {
+        let (width, height) = (self.width, self.height);
+        Grid::new_fn((width, height), |_index, (x, y)| {
+            x == 0 || y == 0 || x == width - 1 || y == height - 1 || rng.f32() < self.fill_probability
+        })
+    }
+
+    /// Run one pass of the automaton. Cells outside of the map count as walls.
+    fn smooth(&self, walls: &Grid<bool>) -> Grid<bool> {
+        walls.map_with_positions(|position, &wall| {
+            let wall_neighbors = Connectivity::Eight
+                .offsets()
+                .iter()
+                .filter(|(dx, dy)| walls.get((position.0 + dx, position.1 + dy)).copied().unwrap_or(true))
+                .count();
+
+            let on_edge = position.0 == 0
+                || position.1 == 0
+                || position.0 == walls.width() as i32 - 1
+                || position.1 == walls.height() as i32 - 1;
+
+            if on_edge {
+                true
+            } else if wall {
+                wall_neighbors >= self.survival_limit
+            } else {
+                wall_neighbors >= self.birth_limit
+            }
+        })
+    }
+
+    /// Fill every cave pocket except the largest one with walls
+    pub fn keep_largest_region(grid: &mut Grid<TerrainType>) {
+        let regions = label_regions(grid, Connectivity::Four, |_, terrain| !terrain.is_impassable());
+        let Some(largest) = regions.largest().map(|region| region.id) else {
+            return;
+        };
+
+        for region in regions.regions.iter().filter(|region| region.id != largest) {
+            for &position in &region.cells {
+                grid[position] = TerrainType::Wall;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_caves_are_one_region_with_both_stairs() {
+        for seed in 0..100 {
+            let mut rng = fastrand::Rng::with_seed(seed);
+            let grid = CaveGenerator::new(60, 40).generate(&mut rng);
+
+            let regions = label_regions(&grid, Connectivity::Four, |_, terrain| !terrain.is_impassable());
+            assert_eq!(regions.regions.len(), 1, "seed {seed} left cave pockets");
+
+            let up_stairs = DungeonGenerator::find_terrain(&grid, TerrainType::UpStairs);
+            let down_stairs = DungeonGenerator::find_terrain(&grid, TerrainType::DownStairs);
+            assert!(up_stairs.is_some() && down_stairs.is_some(), "seed {seed} is missing stairs");
+            assert_ne!(up_stairs, down_stairs);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use brtk::grid::{Grid, GridRect};
+
+use crate::model::{components::TerrainType, generation::DungeonGenerator, ModelConstants};
+
+/// Where each new walker of a `DrunkardGenerator` starts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WalkerSpawn {
+    /// Every walker starts from the center of the map, carving a blob around it
+    #[default]
+    Center,
+    /// Every walker after the first starts from a random tile that was already carved, carving
+    /// long winding tunnels
+    RandomFloor,
+}
+
+/// Generates organic tunnels with random walkers.
+///
+/// Walkers stumble around the map in random cardinal steps, carving floor as they go, until
+/// enough of the map is floor or the walkers run out. Every walker starts on carved floor, so the
+/// level is always connected.
+pub struct DrunkardGenerator {
+    pub width: usize,
+    pub height: usize,
+    /// Most walkers spawned before giving up on reaching the target floor percentage
+    pub walker_count: usize,
+    /// Steps each walker takes before it stops
+    pub walker_lifetime: u32,
+    /// Fraction of the map that should be floor, between 0.0 and 1.0
+    pub target_floor_percent: f32,
+    /// Width of the square carved at every step
+    pub brush_size: i32,
+    pub spawn_mode: WalkerSpawn,
+}
+
+impl Default for DrunkardGenerator {
+    fn default() -> Self {
+        Self {
+            width: ModelConstants::MAP_WIDTH,
+            height: ModelConstants::MAP_HEIGHT,
+            walker_count: 100,
+            walker_lifetime: 200,
+            target_floor_percent: 0.4,
+            brush_size: 1,
+            spawn_mode: WalkerSpawn::default(),
+        }
+    }
+}
+
+impl DrunkardGenerator {
+    /// Create a new drunkard's walk generator with the specified dimensions and default settings
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, ..Default::default() }
+    }
+
+    /// Generate a tunnel level with up and down stairs
+    ///
+    /// Returns a Grid<TerrainType> representing the completed level
+    pub fn generate(&self, rng: &mut fastrand::Rng) -> Grid<TerrainType> {
+        let mut grid = Grid::new_fill((self.width, self.height), TerrainType::Wall);
+        self.layout(&mut grid, rng);
+
+        DungeonGenerator::place_stairs_far_apart(&mut grid, rng);
+
+        grid
+    }
+
+    /// Send the walkers out, carving tunnels into `grid`
+    pub fn layout(&self, grid: &mut Grid<TerrainType>, rng: &mut fastrand::Rng) {
+        // Keep the outer edge of the map solid
+        let interior = GridRect::new(1, 1, grid.width() as i32 - 2, grid.height() as i32 - 2);
+        let target_floor = (interior.area() as f32 * self.target_floor_percent.clamp(0.0, 1.0)) as usize;
+
+        let center = interior.center();
+        let mut floors = vec![center];
+        grid[center] = TerrainType::Floor;
+
+        for walker in 0..self.walker_count {
+            if floors.len() >= target_floor {
+                break;
+            }
+
+            let mut position = match self.spawn_mode {
+                WalkerSpawn::RandomFloor if walker > 0 => floors[rng.usize(0..floors.len())],
+                _ => center,
+            };
+
+            for _ in 0..self.walker_lifetime {
+                self.carve(grid, interior, position, &mut floors);
+                if floors.len() >= target_floor {
+                    break;
+                }
+
+                let (dx, dy) = [(0, 1), (0, -1), (1, 0), (-1, 0)][rng.usize(0..4)];
+                let next = (position.0 + dx, position.1 + dy);
+                if interior.contains(next) {
+                    position = next;
+                }
+            }
+        }
+
+        log::debug!("Drunkard's walk carved {} of {} floor tiles", floors.len(), target_floor);
+    }
+
+    /// Carve the brush around `position`, recording newly carved tiles
+    fn carve(
+        &self,
+        grid: &mut Grid<TerrainType>,
+        interior: GridRect,
+        position: (i32, i32),
+        floors: &mut Vec<(i32, i32)>,
+    ) {
+        let size = self.brush_size.max(1);
+        let brush = GridRect::new(position.0 - (size - 1) / 2, position.1 - (size - 1) / 2, size, size);
+
+        for cell in brush.positions().filter(|&cell| interior.contains(cell)) {
+            if grid[cell] == TerrainType::Wall {
+                grid[cell] = TerrainType::Floor;
+                floors.push(cell);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn floor_count(grid: &Grid<TerrainType>) -> usize {
+        grid.iter().filter(|terrain| !terrain.is_impassable()).count()
+    }
+
+    fn edge_is_solid(grid: &Grid<TerrainType>) -> bool {
+        let (width, height) = (grid.width() as i32, grid.height() as i32);
+        grid.iter_with_positions()
+            .filter(|((x, y), _)| *x == 0 || *y == 0 || *x == width - 1 || *y == height - 1)
+            .all(|(_, terrain)| *terrain == TerrainType::Wall)
+    }
+
+    #[test]
+    fn generated_levels_reach_the_target_and_stay_connected() {
+        for spawn_mode in [WalkerSpawn::Center, WalkerSpawn::RandomFloor] {
+            for seed in 0..50 {
+                let mut rng = fastrand::Rng::with_seed(seed);
+                let generator =
+                    DrunkardGenerator { brush_size: 2, spawn_mode, ..DrunkardGenerator::new(60, 40) };
+                let grid = generator.generate(&mut rng);
+
+                let target_floor = (58.0 * 38.0 * generator.target_floor_percent) as usize;
+                assert!(floor_count(&grid) >= target_floor, "{spawn_mode:?} seed {seed} fell short");
+                assert!(edge_is_solid(&grid), "{spawn_mode:?} seed {seed} carved the edge");
+                assert!(
+                    DungeonGenerator::unreachable_positions(&grid).is_empty(),
+                    "{spawn_mode:?} seed {seed} left walkable tiles unreachable"
+                );
+                assert!(DungeonGenerator::find_terrain(&grid, TerrainType::DownStairs).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn walkers_running_out_stop_short_of_the_target() {
+        let generator =
+            DrunkardGenerator { walker_count: 2, walker_lifetime: 10, ..DrunkardGenerator::new(60, 40) };
+        let mut grid = Grid::new_fill((60, 40), TerrainType::Wall);
+        generator.layout(&mut grid, &mut fastrand::Rng::with_seed(5));
+
+        // Each step carves at most one tile, on top of the starting tile
+        let floors = floor_count(&grid);
+        assert!((1..=21).contains(&floors));
+        assert!(edge_is_solid(&grid));
+    }
+}
@@ -1,12 +1,16 @@
 use bevy::prelude::*;
-use brtk::grid::Grid;
+use brtk::{
+    grid::{Connectivity, Grid},
+    pathfinding::DijkstraMap,
+    region::{flood_fill, label_regions},
+};
 
 use crate::model::{
     components::{Description, Position, TerrainType},
     ModelConstants,
 };
 
-use super::Room;
+use super::{builders::CorridorStyle, Room};
 
 pub struct DungeonGenerator {
     pub width: usize,
@@ -50,6 +54,28 @@ impl DungeonGenerator {
         // Initialize grid with walls
         let mut grid = Grid::new_fill((self.width, self.height), TerrainType::Wall);
 
+        // Place and carve rooms
+        self.layout(&mut grid, rng);
+
+        // Connect each room to the next one
+        CorridorStyle::Dogleg.connect(&mut grid, &self.rooms);
+
+        // Add doors between rooms and corridors
+        Self::place_doors(&mut grid, &self.rooms, rng);
+
+        // Place stairs
+        if !self.rooms.is_empty() {
+            Self::place_stairs(&mut grid, &self.rooms, rng);
+        }
+
+        // Make sure nothing is stranded away from the up stairs
+        Self::connect_unreachable_regions(&mut grid);
+
+        grid
+    }
+
+    /// Place random rooms and carve them into `grid`, without connecting them
+    pub fn layout(&mut self, grid: &mut Grid<TerrainType>, rng: &mut fastrand::Rng) {
         // Clear existing rooms
         self.rooms.clear();
 
@@ -58,32 +84,66 @@ impl DungeonGenerator {
 
         // Carve rooms
         for room in &self.rooms {
-            self.carve_room(&mut grid, room);
+            Self::carve_room(grid, room);
         }
+    }
 
-        // Connect rooms
-        if !self.rooms.is_empty() {
-            let mut rooms = self.rooms.clone();
-            // Sort rooms for consistent corridor generation
-            rooms.sort_by_key(|room| room.center().0 + room.center().1);
-
-            // Connect each room to the next one
-            for i in 0..rooms.len() - 1 {
-                let from = rooms[i].center();
-                let to = rooms[i + 1].center();
-                self.carve_corridor(&mut grid, from, to);
-            }
+    /// Find every walkable position that cannot be reached from the up stairs
+    ///
+    /// Returns an empty list if the grid has no up stairs, as there is no spawn to strand the
+    /// player from.
+    pub fn unreachable_positions(grid: &Grid<TerrainType>) -> Vec<(i32, i32)> {
+        let Some(up_stairs) = Self::find_terrain(grid, TerrainType::UpStairs) else {
+            return Vec::new();
+        };
+
+        let mut unreachable = grid.map(|terrain| !terrain.is_impassable());
+        let walkable = |_, terrain: &TerrainType| !terrain.is_impassable();
+        for position in flood_fill(grid, up_stairs, Connectivity::Four, walkable) {
+            unreachable[position] = false;
         }
 
-        // Add doors between rooms and corridors
-        // self.place_doors(&mut grid, rng);
+        unreachable
+            .iter_with_positions()
+            .filter(|(_, &unreachable)| unreachable)
+            .map(|(position, _)| position)
+            .collect()
+    }
 
-        // Place stairs
-        if !self.rooms.is_empty() {
-            self.place_stairs(&mut grid, rng);
+    /// Find the first position holding the given terrain type
+    pub fn find_terrain(grid: &Grid<TerrainType>, terrain_type: TerrainType) -> Option<(i32, i32)> {
+        grid.iter_with_positions()
+            .find(|(_, terrain)| **terrain == terrain_type)
+            .map(|(position, _)| position)
+    }
+
+    /// Carve a corridor from every region cut off from the up stairs back to the up stairs
+    ///
+    /// A bad room and corridor layout could otherwise strand the player away from parts of the
+    /// level, including the down stairs.
+    pub fn connect_unreachable_regions(grid: &mut Grid<TerrainType>) {
+        let Some(up_stairs) = Self::find_terrain(grid, TerrainType::UpStairs) else {
+            return;
+        };
+
+        let regions = label_regions(grid, Connectivity::Four, |_, terrain| !terrain.is_impassable());
+        let Some(main_region) = regions.region_at(up_stairs).map(|region| region.id) else {
+            return;
+        };
+
+        for region in regions.regions.iter().filter(|region| region.id != main_region) {
+            log::warn!(
+                "Connecting region of {} tiles unreachable from the up stairs at {:?}",
+                region.size(),
+                region.bounds
+            );
+            Self::carve_corridor(grid, region.cells[0], up_stairs);
         }
 
-        grid
+        debug_assert!(
+            Self::unreachable_positions(grid).is_empty(),
+            "Every walkable tile should be reachable"
+        );
     }
 
     /// Generate random non-overlapping rooms within the dungeon boundaries
@@ -118,7 +178,7 @@ impl DungeonGenerator {
     ///
     /// This method modifies the grid in-place, changing all cells within the room's
     /// boundaries from walls to floor tiles.
-    fn carve_room(&self, grid: &mut Grid<TerrainType>, room: &Room) {
+    pub fn carve_room(grid: &mut Grid<TerrainType>, room: &Room) {
         for (x, y) in room.positions() {
             if let Some(cell) = grid.get_mut((x, y)) {
                 *cell = TerrainType::Floor;
@@ -127,8 +187,8 @@ impl DungeonGenerator {
     }
 
     /// Carve a corridor between two points, using either horizontal-first or vertical-first
-    /// approach
-    fn carve_corridor(&self, grid: &mut Grid<TerrainType>, from: (i32, i32), to: (i32, i32)) {
+    /// approach. Only walls are carved, so stairs and doors along the way are kept.
+    pub fn carve_corridor(grid: &mut Grid<TerrainType>, from: (i32, i32), to: (i32, i32)) {
         let (mut x, mut y) = from;
 
         // Alternate between horizontal-first and vertical-first corridors
@@ -140,7 +200,7 @@ impl DungeonGenerator {
             while x != to.0 {
                 x += (to.0 - x).signum();
                 let position = (x, y);
-                if grid.in_bounds(position) {
+                if grid.get(position) == Some(&TerrainType::Wall) {
                     grid[position] = TerrainType::Floor;
                 }
             }
@@ -148,7 +208,7 @@ impl DungeonGenerator {
             while y != to.1 {
                 y += (to.1 - y).signum();
                 let position = (x, y);
-                if grid.in_bounds(position) {
+                if grid.get(position) == Some(&TerrainType::Wall) {
                     grid[position] = TerrainType::Floor;
                 }
             }
@@ -157,7 +217,7 @@ impl DungeonGenerator {
             while y != to.1 {
                 y += (to.1 - y).signum();
                 let position = (x, y);
-                if grid.in_bounds(position) {
+                if grid.get(position) == Some(&TerrainType::Wall) {
                     grid[position] = TerrainType::Floor;
                 }
             }
@@ -165,7 +225,7 @@ impl DungeonGenerator {
             while x != to.0 {
                 x += (to.0 - x).signum();
                 let position = (x, y);
-                if grid.in_bounds(position) {
+                if grid.get(position) == Some(&TerrainType::Wall) {
                     grid[position] = TerrainType::Floor;
                 }
             }
@@ -173,11 +233,11 @@ impl DungeonGenerator {
     }
 
     /// Place doors at suitable locations between rooms and corridors
-    fn place_doors(&self, grid: &mut Grid<TerrainType>, rng: &mut fastrand::Rng) {
+    pub fn place_doors(grid: &mut Grid<TerrainType>, rooms: &[Room], rng: &mut fastrand::Rng) {
         let mut door_candidates = Vec::new();
 
         // Check each room's border for potential entryways
-        for room in &self.rooms {
+        for room in rooms {
             // Get all border positions of the room
             for border_pos in room.border_positions() {
                 // Skip if not a floor (only floors can be doors)
@@ -186,14 +246,11 @@ impl DungeonGenerator {
                 }
 
                 // Check each direction for adjacent floor tiles outside the room
-                let directions = [(-1, 0), (1, 0), (0, -1), (0, 1)];
                 let mut floor_connections_outside = 0;
                 let mut floor_connections_inside = 0;
 
-                for &(dx, dy) in &directions {
-                    let adjacent_pos = (border_pos.0 + dx, border_pos.1 + dy);
-
-                    if grid.in_bounds(adjacent_pos) && grid[adjacent_pos] == TerrainType::Floor {
+                for (adjacent_pos, terrain) in grid.neighbors_4(border_pos) {
+                    if *terrain == TerrainType::Floor {
                         if room.contains(adjacent_pos) {
                             floor_connections_inside += 1;
                         } else {
@@ -214,10 +271,10 @@ impl DungeonGenerator {
         log::info!("Found {} door candidates", door_candidates.len());
 
         // Sort candidates by score (higher is better)
-        door_candidates.sort_by(|a, b| b.2.cmp(&a.2));
+        door_candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.2));
 
         // Determine how many doors to place - roughly one per room, with some randomness
-        let base_door_count = self.rooms.len();
+        let base_door_count = rooms.len();
         let additional_doors = (base_door_count / 2).min(door_candidates.len() / 4);
         let door_count = if additional_doors > 0 {
             base_door_count + rng.usize(0..=additional_doors)
@@ -254,15 +311,15 @@ impl DungeonGenerator {
     }
 
     /// Place up and down stairs in different rooms, away from walls
-    fn place_stairs(&self, grid: &mut Grid<TerrainType>, rng: &mut fastrand::Rng) {
-        if self.rooms.len() < 2 {
+    pub fn place_stairs(grid: &mut Grid<TerrainType>, rooms: &[Room], rng: &mut fastrand::Rng) {
+        if rooms.len() < 2 {
             return; // Need at least 2 rooms for stairs
         }
 
         // Choose two distant rooms for stairs
-        let mut room_indices: Vec<usize> = (0..self.rooms.len()).collect();
+        let mut room_indices: Vec<usize> = (0..rooms.len()).collect();
         room_indices.sort_by_key(|&i| {
-            let room = &self.rooms[i];
+            let room = &rooms[i];
             let center = room.center();
             // Use Manhattan distance from top-left to create a consistent ordering
             center.0 + center.1
@@ -272,64 +329,72 @@ impl DungeonGenerator {
         let first_room_idx = room_indices[0];
         let last_room_idx = room_indices[room_indices.len() - 1];
 
-        // Find suitable positions in first room for up stairs
-        let mut up_stair_candidates = Vec::new();
-        let first_room = &self.rooms[first_room_idx];
-        for (x, y) in first_room.inner_positions() {
-            if grid.in_bounds((x, y)) && grid[(x, y)] == TerrainType::Floor {
-                // Check if position is away from walls (has mostly floor neighbors)
-                let floor_neighbors = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)]
-                    .iter()
-                    .filter(|&(dx, dy)| {
-                        let nx = x + dx;
-                        let ny = y + dy;
-                        grid.in_bounds((nx, ny)) && grid[(nx, ny)] == TerrainType::Floor
-                    })
-                    .count();
+        // Find suitable positions in the first room for up stairs and the last room for down stairs
+        let up_stair_candidates = Self::stair_candidates(grid, &rooms[first_room_idx]);
+        let down_stair_candidates = Self::stair_candidates(grid, &rooms[last_room_idx]);
 
-                // If position has at least 6 floor neighbors, it's good for stairs
-                if floor_neighbors >= 6 {
-                    up_stair_candidates.push((x as usize, y as usize));
-                }
-            }
+        // Place stairs if we have candidates
+        if !up_stair_candidates.is_empty() {
+            let position = up_stair_candidates[rng.usize(0..up_stair_candidates.len())];
+            grid[position] = TerrainType::UpStairs;
         }
 
-        // Find suitable positions in last room for down stairs
-        let mut down_stair_candidates = Vec::new();
-        let last_room = &self.rooms[last_room_idx];
-        for (x, y) in last_room.inner_positions() {
-            if grid.in_bounds((x, y)) && grid[(x, y)] == TerrainType::Floor {
-                // Check if position is away from walls (has mostly floor neighbors)
-                let floor_neighbors = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)]
-                    .iter()
-                    .filter(|&(dx, dy)| {
-                        let nx = x + dx;
-                        let ny = y + dy;
-                        grid.in_bounds((nx, ny)) && grid[(nx, ny)] == TerrainType::Floor
-                    })
-                    .count();
-
-                // If position has at least 6 floor neighbors, it's good for stairs
-                if floor_neighbors >= 6 {
-                    down_stair_candidates.push((x as usize, y as usize));
-                }
-            }
+        if !down_stair_candidates.is_empty() {
+            let position = down_stair_candidates[rng.usize(0..down_stair_candidates.len())];
+            grid[position] = TerrainType::DownStairs;
         }
+    }
 
-        // Place stairs if we have candidates
-        if !up_stair_candidates.is_empty() {
-            let (x, y) = up_stair_candidates[rng.usize(0..up_stair_candidates.len())];
-            // Convert to i32 for grid indexing
-            grid[(x as i32, y as i32)] = TerrainType::UpStairs;
+    /// Place up and down stairs on floor tiles as far apart as possible, for layouts without rooms
+    ///
+    /// The up stairs go on the floor tile farthest from a random floor tile, and the down stairs
+    /// on the floor tile farthest from the up stairs.
+    pub fn place_stairs_far_apart(grid: &mut Grid<TerrainType>, rng: &mut fastrand::Rng) {
+        let floors: Vec<(i32, i32)> = grid
+            .iter_with_positions()
+            .filter(|(_, terrain)| **terrain == TerrainType::Floor)
+            .map(|(position, _)| position)
+            .collect();
+        if floors.len() < 2 {
+            return;
         }
 
-        if !down_stair_candidates.is_empty() {
-            let (x, y) = down_stair_candidates[rng.usize(0..down_stair_candidates.len())];
-            // Convert to i32 for grid indexing
-            grid[(x as i32, y as i32)] = TerrainType::DownStairs;
+        let start = floors[rng.usize(0..floors.len())];
+        let Some(up_stairs) = Self::farthest_floor(grid, start) else {
+            return;
+        };
+        grid[up_stairs] = TerrainType::UpStairs;
+
+        if let Some(down_stairs) = Self::farthest_floor(grid, up_stairs) {
+            grid[down_stairs] = TerrainType::DownStairs;
         }
     }
 
+    /// Find the floor tile with the longest walk from `from`
+    fn farthest_floor(grid: &Grid<TerrainType>, from: (i32, i32)) -> Option<(i32, i32)> {
+        let distances =
+            DijkstraMap::new(grid, [from], Connectivity::Four, |_, terrain| !terrain.is_impassable());
+
+        grid.iter_with_positions()
+            .filter(|(_, terrain)| **terrain == TerrainType::Floor)
+            .filter_map(|(position, _)| distances.get(position).map(|distance| (position, distance)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(position, _)| position)
+    }
+
+    /// Collect the floor positions inside a room that are away from walls
+    pub fn stair_candidates(grid: &Grid<TerrainType>, room: &Room) -> Vec<(i32, i32)> {
+        room.inner_positions()
+            .filter(|&position| grid.get(position) == Some(&TerrainType::Floor))
+            .filter(|&position| {
+                // If position has at least 6 floor neighbors, it's good for stairs
+                let floor_neighbors =
+                    grid.neighbors_8(position).filter(|(_, terrain)| **terrain == TerrainType::Floor).count();
+                floor_neighbors >= 6
+            })
+            .collect()
+    }
+
     /// Convert the terrain grid into actual game entities
     ///
     /// This method creates Bevy entities for each cell in the terrain grid, applying:
@@ -339,23 +404,76 @@ impl DungeonGenerator {
     ///
     /// Returns a Grid<Entity> with the entity IDs corresponding to each position,
     /// which can be used to reference these entities later.
-    pub fn generate_entities(
-        &self,
-        commands: &mut Commands,
-        terrain_grid: &Grid<TerrainType>,
-    ) -> Grid<Entity> {
-        Grid::new_fn((self.width, self.height), |_index, (x, y)| {
-            // Convert to i32 for grid indexing
-            let terrain_type = terrain_grid[(x as i32, y as i32)].clone();
+    pub fn generate_entities(commands: &mut Commands, terrain_grid: &Grid<TerrainType>) -> Grid<Entity> {
+        terrain_grid.map_with_positions(|(x, y), terrain_type| {
+            let terrain_type = terrain_type.clone();
             let description = match terrain_type {
                 TerrainType::Floor => Description::new("Floor"),
                 TerrainType::Wall => Description::new("Wall"),
                 TerrainType::Door => Description::new("Door"),
+                TerrainType::OpenDoor => Description::new("Open door"),
                 TerrainType::UpStairs => Description::new("Stairs leading up"),
                 TerrainType::DownStairs => Description::new("Stairs leading down"),
             };
 
-            commands.spawn((terrain_type, description, Position::new(x as i32, y as i32))).id()
+            commands.spawn((terrain_type, description, Position::new(x, y))).id()
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_utils::{level, level_text};
+
+    #[test]
+    fn generated_levels_are_fully_reachable() {
+        for seed in 0..200 {
+            let mut rng = fastrand::Rng::with_seed(seed);
+            let grid = DungeonGenerator::new(60, 40).generate(&mut rng);
+
+            assert!(DungeonGenerator::find_terrain(&grid, TerrainType::UpStairs).is_some());
+            assert!(
+                DungeonGenerator::unreachable_positions(&grid).is_empty(),
+                "seed {seed} left walkable tiles unreachable"
+            );
+        }
+    }
+
+    #[test]
+    fn stranded_regions_are_connected_to_the_up_stairs() {
+        let mut grid = level(
+            "
+#########
+#<.##...#
+#..##.>.#
+#########",
+        );
+        assert!(!DungeonGenerator::unreachable_positions(&grid).is_empty());
+
+        DungeonGenerator::connect_unreachable_regions(&mut grid);
+        assert!(DungeonGenerator::unreachable_positions(&grid).is_empty());
+    }
+
+    #[test]
+    fn corridors_only_carve_walls() {
+        let mut grid = level(
+            "
+#######
+#<#+#>#
+#######",
+        );
+        DungeonGenerator::carve_corridor(&mut grid, (1, 1), (5, 1));
+
+        assert_eq!(level_text(&grid), "#######\n#<.+.>#\n#######");
+    }
+
+    #[test]
+    fn stairs_far_apart_go_at_both_ends_of_a_corridor() {
+        let mut grid = level("#######\n#.....#\n#######");
+        DungeonGenerator::place_stairs_far_apart(&mut grid, &mut fastrand::Rng::with_seed(3));
+
+        let text = level_text(&grid);
+        assert!(text == "#######\n#<...>#\n#######" || text == "#######\n#>...<#\n#######", "{text}");
+    }
+}
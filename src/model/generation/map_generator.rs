@@ -0,0 +1,150 @@
+use brtk::grid::Grid;
+
+use crate::model::components::TerrainType;
+
+use super::{
+    builders::{
+        BuilderChain, CorridorBuilder, CorridorStyle, CullUnreachableBuilder, DoorBuilder, RoomCullBuilder,
+        StairsBuilder, VaultBuilder,
+    },
+    BspGenerator, CaveGenerator, DrunkardGenerator, DungeonGenerator, Room, Spawn, Vault, WalkerSpawn,
+    WfcGenerator,
+};
+
+/// A level layout algorithm
+pub trait MapGenerator: Send + Sync {
+    /// Generate a level, surrounded by walls and holding up and down stairs
+    fn generate(&mut self, rng: &mut fastrand::Rng) -> Grid<TerrainType>;
+
+    /// Rooms of the last generated level, empty for layouts without rooms
+    fn rooms(&self) -> &[Room] {
+        &[]
+    }
+
+    /// Monsters and items the last generated level asks for, such as the contents of its vaults
+    fn spawns(&self) -> &[Spawn] {
+        &[]
+    }
+}
+
+impl MapGenerator for DungeonGenerator {
+    fn generate(&mut self, rng: &mut fastrand::Rng) -> Grid<TerrainType> {
+        DungeonGenerator::generate(self, rng)
+    }
+
+    fn rooms(&self) -> &[Room] {
+        &self.rooms
+    }
+}
+
+impl MapGenerator for BspGenerator {
+    fn generate(&mut self, rng: &mut fastrand::Rng) -> Grid<TerrainType> {
+        BspGenerator::generate(self, rng)
+    }
+
+    fn rooms(&self) -> &[Room] {
+        &self.rooms
+    }
+}
+
+impl MapGenerator for CaveGenerator {
+    fn generate(&mut self, rng: &mut fastrand::Rng) -> Grid<TerrainType> {
+        CaveGenerator::generate(self, rng)
+    }
+}
+
+impl MapGenerator for DrunkardGenerator {
+    fn generate(&mut self, rng: &mut fastrand::Rng) -> Grid<TerrainType> {
+        DrunkardGenerator::generate(self, rng)
+    }
+}
+
+impl MapGenerator for WfcGenerator {
+    fn generate(&mut self, rng: &mut fastrand::Rng) -> Grid<TerrainType> {
+        WfcGenerator::generate(self, rng)
+    }
+}
+
+/// Layout algorithms a level can be generated with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LevelLayout {
+    /// Rooms joined by corridors
+    #[default]
+    Dungeon,
+    Bsp,
+    Cave,
+    /// Winding tunnels dug by walkers starting from carved floor
+    Tunnels,
+    Wfc,
+    /// Rooms culled and joined by a `BuilderChain`, with vaults
+    Vaults,
+}
+
+impl LevelLayout {
+    /// Create a generator for this layout with the specified dimensions
+    pub fn generator(self, width: usize, height: usize) -> Box<dyn MapGenerator> {
+        match self {
+            Self::Dungeon => Box::new(DungeonGenerator::new(width, height)),
+            Self::Bsp => Box::new(BspGenerator::new(width, height)),
+            Self::Cave => Box::new(CaveGenerator::new(width, height)),
+            Self::Tunnels => Box::new(DrunkardGenerator {
+                spawn_mode: WalkerSpawn::RandomFloor,
+                ..DrunkardGenerator::new(width, height)
+            }),
+            Self::Wfc => Box::new(WfcGenerator::new(width, height)),
+            Self::Vaults => Box::new(
+                BuilderChain::new(
+                    width,
+                    height,
+                    DungeonGenerator { max_rooms: 25, ..DungeonGenerator::new(width, height) },
+                )
+                .with(RoomCullBuilder::new(24).with_max_rooms(12))
+                .with(CorridorBuilder::new(CorridorStyle::NearestNeighbor))
+                .with(DoorBuilder)
+                .with(CullUnreachableBuilder)
+                .with(StairsBuilder)
+                .with(VaultBuilder::new(Vault::defaults(), 2)),
+            ),
+        }
+    }
+}
+
+/// Depths laid out with something other than the default `LevelLayout`
+const DEPTH_LAYOUTS: &[(u32, LevelLayout)] = &[
+    (2, LevelLayout::Bsp),
+    (3, LevelLayout::Cave),
+    (4, LevelLayout::Tunnels),
+    (5, LevelLayout::Wfc),
+    (6, LevelLayout::Vaults),
+];
+
+/// The layout of the level at `depth`
+fn layout_for_depth(depth: u32) -> LevelLayout {
+    DEPTH_LAYOUTS
+        .iter()
+        .find(|(layout_depth, _)| *layout_depth == depth)
+        .map(|(_, layout)| *layout)
+        .unwrap_or_default()
+}
+
+/// Picks the generator for the level at `depth`
+pub fn generator_for_depth(depth: u32, width: usize, height: usize) -> Box<dyn MapGenerator> {
+    layout_for_depth(depth).generator(width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depths_without_an_entry_use_the_default_layout() {
+        assert_eq!(layout_for_depth(1), LevelLayout::Dungeon);
+        assert_eq!(layout_for_depth(2), LevelLayout::Bsp);
+        assert_eq!(layout_for_depth(100), LevelLayout::Dungeon);
+
+        for (index, (depth, _)) in DEPTH_LAYOUTS.iter().enumerate() {
+            let repeated = DEPTH_LAYOUTS[index + 1..].iter().any(|(other, _)| other == depth);
+            assert!(!repeated, "depth {depth} has two layouts");
+        }
+    }
+}
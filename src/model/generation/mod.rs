@@ -1,5 +1,20 @@
+mod bsp_generator;
+pub mod builders;
+mod cave_generator;
+mod drunkard_generator;
 mod dungeon_generator;
+mod map_generator;
 mod room;
+mod spawn;
+mod vault;
+mod wfc_generator;
 
+pub use bsp_generator::BspGenerator;
+pub use cave_generator::CaveGenerator;
+pub use drunkard_generator::{DrunkardGenerator, WalkerSpawn};
 pub use dungeon_generator::DungeonGenerator;
+pub use map_generator::{generator_for_depth, LevelLayout, MapGenerator};
 pub use room::Room;
+pub use spawn::{Spawn, SpawnKind};
+pub use vault::{LegendEntry, Vault, VaultLegend, VaultPlacement};
+pub use wfc_generator::WfcGenerator;
@@ -0,0 +1,13 @@
+/// Something a generator asks to be spawned on the level it generated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpawnKind {
+    Monster,
+    Item { name: String, glyph: char },
+}
+
+/// A spawn request at a position of a generated level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spawn {
+    pub position: (i32, i32),
+    pub kind: SpawnKind,
+}
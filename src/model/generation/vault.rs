@@ -0,0 +1,367 @@
+use bevy::utils::HashMap;
+use brtk::{
+    grid::{Connectivity, Grid, GridParseError, GridRect},
+    region::{flood_fill, label_regions},
+};
+
+use crate::model::{components::TerrainType, generation::DungeonGenerator};
+
+use super::{Spawn, SpawnKind};
+
+/// What a character of a vault template stands for
+#[derive(Clone, PartialEq, Eq)]
+pub struct LegendEntry {
+    /// Terrain stamped into the level, or None to keep the terrain underneath
+    pub terrain: Option<TerrainType>,
+    pub spawn: Option<SpawnKind>,
+}
+
+impl LegendEntry {
+    /// Keep the level as it is
+    pub const KEEP: Self = Self { terrain: None, spawn: None };
+
+    pub fn terrain(terrain: TerrainType) -> Self {
+        Self { terrain: Some(terrain), spawn: None }
+    }
+
+    /// A monster standing on floor
+    pub fn monster() -> Self {
+        Self { terrain: Some(TerrainType::Floor), spawn: Some(SpawnKind::Monster) }
+    }
+
+    /// An item lying on floor, drawn with `glyph`
+    pub fn item(name: impl ToString, glyph: char) -> Self {
+        Self {
+            terrain: Some(TerrainType::Floor),
+            spawn: Some(SpawnKind::Item { name: name.to_string(), glyph }),
+        }
+    }
+}
+
+/// Maps the characters of vault templates to what they stand for
+#[derive(Clone)]
+pub struct VaultLegend {
+    entries: HashMap<char, LegendEntry>,
+}
+
+impl Default for VaultLegend {
+    /// Terrain glyphs stand for their terrain, `_` keeps the level as it is, `E` is a monster, `$`
+    /// is gold and `!` is a potion
+    fn default() -> Self {
+        let terrain = ['.', '#', '+', '\'', '<', '>'].into_iter().filter_map(|glyph| {
+            TerrainType::from_glyph(glyph).map(|terrain| (glyph, LegendEntry::terrain(terrain)))
+        });
+
+        let entries = terrain
+            .chain([
+                ('_', LegendEntry::KEEP),
+                ('E', LegendEntry::monster()),
+                ('$', LegendEntry::item("Gold", '$')),
+                ('!', LegendEntry::item("Potion", '!')),
+            ])
+            .collect();
+
+        Self { entries }
+    }
+}
+
+impl VaultLegend {
+    /// A legend without any entries
+    pub fn empty() -> Self {
+        Self { entries: HashMap::new() }
+    }
+
+    /// Add or replace the entry for `glyph`
+    pub fn with(mut self, glyph: char, entry: LegendEntry) -> Self {
+        self.entries.insert(glyph, entry);
+        self
+    }
+
+    pub fn get(&self, glyph: char) -> Option<&LegendEntry> {
+        self.entries.get(&glyph)
+    }
+}
+
+/// A handcrafted room, such as a vault or a shrine, stamped into generated levels.
+///
+/// Templates are drawn the way the map is displayed, with north on the first line. A vault is
+/// only placed where all of its walkable tiles can be reached and where it cuts nothing else off.
+#[derive(Clone)]
+pub struct Vault {
+    pub name: String,
+    pub template: Grid<LegendEntry>,
+    /// Try the template rotated by quarter turns
+    pub rotate: bool,
+    /// Try the template mirrored
+    pub mirror: bool,
+}
+
+/// Where a vault fits in a level
+#[derive(Clone)]
+pub struct VaultPlacement {
+    /// The template, rotated or mirrored as placed
+    pub template: Grid<LegendEntry>,
+    /// Position of the template's first cell in the level
+    pub offset: (i32, i32),
+}
+
+impl VaultPlacement {
+    /// Area of the level covered by the vault
+    pub fn bounds(&self) -> GridRect {
+        let (width, height) = (self.template.width() as i32, self.template.height() as i32);
+        GridRect::new(self.offset.0, self.offset.1, width, height)
+    }
+
+    /// Stamp the vault into `grid`, returning what it asks to spawn
+    pub fn stamp(&self, grid: &mut Grid<TerrainType>) -> Vec<Spawn> {
+        let mut spawns = Vec::new();
+
+        for ((x, y), entry) in self.template.iter_with_positions() {
+            let position = (self.offset.0 + x, self.offset.1 + y);
+            if let Some(terrain) = &entry.terrain {
+                grid[position] = terrain.clone();
+            }
+            if let Some(kind) = &entry.spawn {
+                spawns.push(Spawn { position, kind: kind.clone() });
+            }
+        }
+
+        spawns
+    }
+}
+
+impl Vault {
+    /// Parse a vault template, mapping its characters with `legend`
+    pub fn from_text(name: impl ToString, text: &str, legend: &VaultLegend) -> Result<Self, GridParseError> {
+        // Rows are flipped so north, drawn first, ends up at the highest y
+        let template = Grid::from_text(text, |glyph| legend.get(glyph).cloned())?.flip_vertical();
+        Ok(Self { name: name.to_string(), template, rotate: true, mirror: true })
+    }
+
+    pub fn with_rotation(mut self, rotate: bool) -> Self {
+        self.rotate = rotate;
+        self
+    }
+
+    pub fn with_mirroring(mut self, mirror: bool) -> Self {
+        self.mirror = mirror;
+        self
+    }
+
+    /// Every orientation the vault may be placed in
+    pub fn variants(&self) -> Vec<Grid<LegendEntry>> {
+        let mut variants = vec![self.template.clone()];
+        if self.rotate {
+            let template = &self.template;
+            variants.extend([template.rotate_90(), template.rotate_180(), template.rotate_270()]);
+        }
+        if self.mirror {
+            let mirrored: Vec<_> = variants.iter().map(Grid::flip_horizontal).collect();
+            variants.extend(mirrored);
+        }
+
+        variants
+    }
+
+    /// Place the vault at a random spot of `grid` where it fits outside of the `reserved` areas,
+    /// trying at most `max_attempts` spots. Returns where the vault was placed and what it asks
+    /// to spawn, or None if no spot was found.
+    pub fn place(
+        &self,
+        grid: &mut Grid<TerrainType>,
+        reserved: &[GridRect],
+        rng: &mut fastrand::Rng,
+        max_attempts: usize,
+    ) -> Option<(VaultPlacement, Vec<Spawn>)> {
+        let mut candidates = self.candidates(grid);
+        candidates.retain(|placement| !reserved.iter().any(|area| area.intersects(&placement.bounds())));
+        rng.shuffle(&mut candidates);
+
+        let anchor = Self::anchor(grid)?;
+        let reachable = Self::reachable_from(grid, anchor);
+
+        for placement in candidates.into_iter().take(max_attempts) {
+            let mut stamped = grid.clone();
+            let spawns = placement.stamp(&mut stamped);
+
+            if Self::keeps_connectivity(&stamped, &reachable, anchor, placement.bounds()) {
+                log::debug!("Placed vault {} at {:?}", self.name, placement.offset);
+                *grid = stamped;
+                return Some((placement, spawns));
+            }
+        }
+
+        log::debug!("No spot found for vault {}", self.name);
+        None
+    }
+
+    /// Placements inside the level that keep its stairs and touch walkable tiles, which are the
+    /// only ones that can connect to the rest of the level
+    pub fn candidates(&self, grid: &Grid<TerrainType>) -> Vec<VaultPlacement> {
+        let (width, height) = (grid.width() as i32, grid.height() as i32);
+        let mut candidates = Vec::new();
+
+        for template in self.variants() {
+            let (vault_width, vault_height) = (template.width() as i32, template.height() as i32);
+
+            // Keep the outer edge of the map solid
+            for y in 1..=height - 1 - vault_height {
+                for x in 1..=width - 1 - vault_width {
+                    if Self::fits(grid, &template, (x, y)) {
+                        candidates.push(VaultPlacement { template: template.clone(), offset: (x, y) });
+                    }
+                }
+            }
+        }
+
+        candidates
+    }
+
+    fn fits(grid: &Grid<TerrainType>, template: &Grid<LegendEntry>, offset: (i32, i32)) -> bool {
+        let keeps_stairs = template.iter_with_positions().all(|((x, y), entry)| {
+            let below = &grid[(offset.0 + x, offset.1 + y)];
+            let is_stairs = matches!(below, TerrainType::UpStairs | TerrainType::DownStairs);
+            !is_stairs || entry.terrain.as_ref().is_none_or(|terrain| terrain == below)
+        });
+
+        let (width, height) = (template.width() as i32, template.height() as i32);
+        let surroundings = GridRect::new(offset.0, offset.1, width, height).expand(1);
+        let touches_walkable = grid.iter_rect(surroundings).any(|(_, terrain)| !terrain.is_impassable());
+
+        keeps_stairs && touches_walkable
+    }
+
+    /// Tile every other tile must stay reachable from: the up stairs, or the largest walkable area
+    fn anchor(grid: &Grid<TerrainType>) -> Option<(i32, i32)> {
+        DungeonGenerator::find_terrain(grid, TerrainType::UpStairs).or_else(|| {
+            let regions = label_regions(grid, Connectivity::Four, |_, terrain| !terrain.is_impassable());
+            regions.largest().map(|region| region.cells[0])
+        })
+    }
+
+    fn reachable_from(grid: &Grid<TerrainType>, anchor: (i32, i32)) -> Grid<bool> {
+        let mut reachable = Grid::new_fill(grid.size(), false);
+        for position in flood_fill(grid, anchor, Connectivity::Four, |_, terrain| !terrain.is_impassable()) {
+            reachable[position] = true;
+        }
+
+        reachable
+    }
+
+    /// Whether every walkable tile that was reachable before, and every walkable tile of the
+    /// vault, is reachable after stamping
+    fn keeps_connectivity(
+        stamped: &Grid<TerrainType>,
+        reachable_before: &Grid<bool>,
+        anchor: (i32, i32),
+        bounds: GridRect,
+    ) -> bool {
+        if stamped[anchor].is_impassable() {
+            return false;
+        }
+
+        let reachable = Self::reachable_from(stamped, anchor);
+        stamped.iter_with_positions().all(|(position, terrain)| {
+            let must_reach = bounds.contains(position) || reachable_before[position];
+            terrain.is_impassable() || !must_reach || reachable[position]
+        })
+    }
+
+    /// Vaults placed in generated levels
+    pub fn defaults() -> Vec<Vault> {
+        let legend = VaultLegend::default();
+
+        [
+            (
+                "Treasure vault",
+                "
+#######
+#$.E.$#
+#.....#
+###+###
+",
+            ),
+            (
+                "Shrine",
+                "
+_....._
+.#...#.
+...!...
+.#...#.
+_....._
+",
+            ),
+            (
+                "Guard post",
+                "
+##+##
+#E.E#
+#...#
+##.##
+",
+            ),
+        ]
+        .into_iter()
+        .map(|(name, text)| Vault::from_text(name, text, &legend).expect("Built-in vaults should parse"))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_utils::level;
+
+    #[test]
+    fn templates_read_north_first() {
+        let vault = Vault::from_text("Test", "#E#\n.$.", &VaultLegend::default()).unwrap();
+
+        assert!(vault.template[(1, 1)] == LegendEntry::monster());
+        assert!(vault.template[(1, 0)] == LegendEntry::item("Gold", '$'));
+        assert_eq!(vault.variants().len(), 8);
+        assert_eq!(vault.with_rotation(false).with_mirroring(false).variants().len(), 1);
+    }
+
+    #[test]
+    fn default_vaults_keep_the_level_under_underscores() {
+        let shrine = Vault::defaults().into_iter().find(|vault| vault.name == "Shrine").unwrap();
+
+        assert_eq!(shrine.template.size(), (7, 5));
+        for corner in [(0, 0), (6, 0), (0, 4), (6, 4)] {
+            assert!(shrine.template[corner] == LegendEntry::KEEP);
+        }
+        assert!(shrine.template[(1, 0)] == LegendEntry::terrain(TerrainType::Floor));
+    }
+
+    #[test]
+    fn placed_vaults_keep_the_level_connected() {
+        let mut grid = level(
+            "
+##########
+#<.......#
+#........#
+#........#
+#.......>#
+##########
+",
+        );
+        let vault = Vault::from_text("Wall", "###\n#E#", &VaultLegend::default()).unwrap();
+        let mut rng = fastrand::Rng::with_seed(7);
+
+        let mut placed = 0;
+        for _ in 0..10 {
+            let mut stamped = grid.clone();
+            let Some((_, spawns)) = vault.place(&mut stamped, &[], &mut rng, 100) else {
+                continue;
+            };
+            placed += 1;
+
+            assert!(DungeonGenerator::unreachable_positions(&stamped).is_empty());
+            assert!(DungeonGenerator::find_terrain(&stamped, TerrainType::DownStairs).is_some());
+            assert!(spawns.iter().all(|spawn| stamped[spawn.position] == TerrainType::Floor));
+            grid = stamped;
+        }
+
+        assert!(placed > 0);
+    }
+}
@@ -0,0 +1,552 @@
+use bevy::utils::HashMap;
+use brtk::grid::{Grid, GridParseError};
+
+use crate::model::{
+    components::TerrainType,
+    generation::{CaveGenerator, DungeonGenerator},
+    ModelConstants,
+};
+
+/// Sample used when no other samples are given: rooms of different sizes joined by doors and
+/// corridors
+const DEFAULT_SAMPLE: &str = "
+##################
+#.....#####......#
+#.....+...+......#
+#.....###.#......#
+###+#####.####+###
+###.#####.####.###
+#.....#.....#....#
+#.....+.....+....#
+#.....#.....#....#
+####+####.#####+##
+####.####.#####.##
+#.......#......###
+#.......+......###
+##################
+";
+
+/// Offsets to the right, left, north and south neighbors. The opposite of direction `d` is
+/// `d ^ 1`.
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// Generates levels with the overlapping model of wave function collapse.
+///
+/// Every NxN window of the sample maps is learned as a pattern. The output starts with every
+/// pattern possible everywhere, then the cell with the fewest remaining patterns is collapsed to
+/// one of them, weighted by how often it appears in the samples, and the choice is propagated to
+/// the neighbors until every cell holds one pattern. Overlapping patterns always agree, so the
+/// output only holds NxN windows seen in the samples.
+///
+/// When a choice leaves a cell without any pattern, it is undone and banned, backtracking further
+/// as needed. Attempts that backtrack too often are restarted from scratch. The finished level
+/// keeps only its largest connected area, and the stairs are placed as far apart as it allows.
+pub struct WfcGenerator {
+    pub width: usize,
+    pub height: usize,
+    pub samples: Vec<Grid<TerrainType>>,
+    /// Width and height of the learned patterns. Larger patterns copy bigger features of the
+    /// samples, but contradict more often.
+    pub pattern_size: usize,
+    /// Also learn the rotated and mirrored samples
+    pub symmetry: bool,
+    /// Attempts made after the first one before giving up
+    pub max_restarts: u32,
+    /// Choices undone in one attempt before restarting it
+    pub max_backtracks: u32,
+    /// Smallest fraction of the map the connected area must cover, between 0.0 and 1.0
+    pub min_floor_percent: f32,
+}
+
+impl Default for WfcGenerator {
+    fn default() -> Self {
+        let sample = Self::parse_sample(DEFAULT_SAMPLE).expect("Default WFC sample should parse");
+        Self {
+            width: ModelConstants::MAP_WIDTH,
+            height: ModelConstants::MAP_HEIGHT,
+            samples: vec![sample],
+            pattern_size: 3,
+            symmetry: true,
+            max_restarts: 10,
+            max_backtracks: 500,
+            min_floor_percent: 0.25,
+        }
+    }
+}
+
+impl WfcGenerator {
+    /// Create a new WFC generator with the specified dimensions, learning from the default sample
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, ..Default::default() }
+    }
+
+    /// Create a new WFC generator learning from sample maps drawn with terrain glyphs, north first
+    pub fn with_samples(width: usize, height: usize, samples: &[&str]) -> Result<Self, GridParseError> {
+        let samples = samples.iter().map(|sample| Self::parse_sample(sample)).collect::<Result<_, _>>()?;
+        Ok(Self { samples, ..Self::new(width, height) })
+    }
+
+    fn parse_sample(text: &str) -> Result<Grid<TerrainType>, GridParseError> {
+        Ok(Grid::from_text(text, TerrainType::from_glyph)?.flip_vertical())
+    }
+
+    /// Generate a level with up and down stairs
+    ///
+    /// Falls back to a cave level if every attempt fails, such as when the samples are smaller
+    /// than the pattern size.
+    pub fn generate(&self, rng: &mut fastrand::Rng) -> Grid<TerrainType> {
+        let patterns = Patterns::learn(&self.samples, self.pattern_size, self.symmetry);
+
+        if patterns.is_empty() || self.width < self.pattern_size || self.height < self.pattern_size {
+            log::warn!("WFC samples hold no {0}x{0} patterns fitting the map", self.pattern_size);
+        } else {
+            for attempt in 0..=self.max_restarts {
+                let level = self.synthesize(&patterns, rng).and_then(|grid| self.finish(grid, rng));
+                if let Some(level) = level {
+                    log::debug!(
+                        "WFC level generated from {} patterns after {} restarts",
+                        patterns.len(),
+                        attempt
+                    );
+                    return level;
+                }
+            }
+
+            log::warn!("WFC failed {} times, generating a cave instead", self.max_restarts + 1);
+        }
+
+        CaveGenerator::new(self.width, self.height).generate(rng)
+    }
+
+    /// Collapse a wave of patterns into terrain, or None if it backtracked too often
+    fn synthesize(&self, patterns: &Patterns, rng: &mut fastrand::Rng) -> Option<Grid<TerrainType>> {
+        let size = patterns.size;
+        let mut wave = Wave::new(self.width - size + 1, self.height - size + 1, patterns.len());
+
+        // Trail length, cell and pattern of every choice, so it can be undone
+        let mut choices: Vec<(usize, usize, usize)> = Vec::new();
+        let mut backtracks = 0;
+
+        while let Some(cell) = wave.most_constrained(rng) {
+            let pattern = patterns.choose(wave.patterns(cell), rng);
+            choices.push((wave.trail_len(), cell, pattern));
+
+            let mut collapsed = vec![0; wave.words];
+            collapsed[pattern / 64] = 1 << (pattern % 64);
+            wave.set(cell, &collapsed);
+            if wave.propagate(patterns, cell) {
+                continue;
+            }
+
+            // Undo choices until banning one of them leaves the wave consistent
+            loop {
+                backtracks += 1;
+                let (trail_len, cell, pattern) = choices.pop()?;
+                if backtracks > self.max_backtracks {
+                    log::debug!("WFC gave up after {} backtracks", backtracks - 1);
+                    return None;
+                }
+
+                wave.undo(trail_len);
+                let mut banned = wave.patterns(cell).to_vec();
+                banned[pattern / 64] &= !(1 << (pattern % 64));
+                if banned.iter().all(|&word| word == 0) {
+                    continue;
+                }
+
+                wave.set(cell, &banned);
+                if wave.propagate(patterns, cell) {
+                    break;
+                }
+            }
+        }
+
+        let mut grid = Grid::new_fill((self.width, self.height), TerrainType::Wall);
+        for cell in 0..wave.len() {
+            let (x, y) = wave.position(cell);
+            let pattern = &patterns.cells[wave.first_pattern(cell)?];
+            for (index, &tile) in pattern.iter().enumerate() {
+                let (dx, dy) = ((index % size) as i32, (index / size) as i32);
+                grid[(x + dx, y + dy)] = patterns.tiles[tile as usize].clone();
+            }
+        }
+
+        Some(grid)
+    }
+
+    /// Seal the map edge, keep the largest connected area and place the stairs, or None if the
+    /// level is too small to play
+    fn finish(&self, mut grid: Grid<TerrainType>, rng: &mut fastrand::Rng) -> Option<Grid<TerrainType>> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        for ((x, y), terrain) in grid.iter_mut_with_positions() {
+            if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+                *terrain = TerrainType::Wall;
+            } else if matches!(terrain, TerrainType::UpStairs | TerrainType::DownStairs) {
+                // Stairs copied from the samples would lead nowhere
+                *terrain = TerrainType::Floor;
+            }
+        }
+
+        CaveGenerator::keep_largest_region(&mut grid);
+
+        let walkable = grid.iter().filter(|terrain| !terrain.is_impassable()).count();
+        let min_walkable = ((width - 2) * (height - 2)) as f32 * self.min_floor_percent.clamp(0.0, 1.0);
+        if (walkable as f32) < min_walkable {
+            log::debug!("WFC level only has {} walkable tiles, {} needed", walkable, min_walkable);
+            return None;
+        }
+
+        DungeonGenerator::place_stairs_far_apart(&mut grid, rng);
+        let has_up_stairs = DungeonGenerator::find_terrain(&grid, TerrainType::UpStairs).is_some();
+        let has_down_stairs = DungeonGenerator::find_terrain(&grid, TerrainType::DownStairs).is_some();
+
+        (has_up_stairs && has_down_stairs).then_some(grid)
+    }
+}
+
+/// The NxN patterns learned from the samples, and which patterns may neighbor each other
+struct Patterns {
+    size: usize,
+    /// Terrain of each tile index
+    tiles: Vec<TerrainType>,
+    /// Tile indices of each pattern, row by row
+    cells: Vec<Vec<u8>>,
+    /// How often each pattern appears in the samples
+    weights: Vec<f32>,
+    /// For each direction and pattern, the set of patterns that may be its neighbor that way
+    compatible: [Vec<Vec<u64>>; 4],
+}
+
+impl Patterns {
+    fn learn(samples: &[Grid<TerrainType>], size: usize, symmetry: bool) -> Self {
+        let mut tiles: Vec<TerrainType> = Vec::new();
+        let mut indices: HashMap<Vec<u8>, usize> = HashMap::new();
+        let mut cells: Vec<Vec<u8>> = Vec::new();
+        let mut weights: Vec<f32> = Vec::new();
+
+        for sample in samples {
+            let sample = sample.map(|terrain| {
+                let index = tiles.iter().position(|tile| tile == terrain).unwrap_or_else(|| {
+                    tiles.push(terrain.clone());
+                    tiles.len() - 1
+                });
+                index as u8
+            });
+
+            let variants = if symmetry {
+                let rotations = [sample.rotate_90(), sample.rotate_180(), sample.rotate_270(), sample];
+                let mirrored: Vec<_> = rotations.iter().map(Grid::flip_horizontal).collect();
+                rotations.into_iter().chain(mirrored).collect()
+            } else {
+                vec![sample]
+            };
+
+            for variant in &variants {
+                let (width, height) = (variant.width(), variant.height());
+                for y in 0..=height.saturating_sub(size) {
+                    for x in 0..=width.saturating_sub(size) {
+                        if x + size > width || y + size > height {
+                            continue;
+                        }
+
+                        let pattern: Vec<u8> = (0..size * size)
+                            .map(|index| variant[((x + index % size) as i32, (y + index / size) as i32)])
+                            .collect();
+
+                        let index = *indices.entry(pattern.clone()).or_insert_with(|| {
+                            cells.push(pattern);
+                            weights.push(0.0);
+                            cells.len() - 1
+                        });
+                        weights[index] += 1.0;
+                    }
+                }
+            }
+        }
+
+        let words = cells.len().div_ceil(64);
+        let compatible = std::array::from_fn(|direction| {
+            let (dx, dy) = DIRECTIONS[direction];
+            cells
+                .iter()
+                .map(|pattern| {
+                    let mut allowed = vec![0; words];
+                    for (index, other) in cells.iter().enumerate() {
+                        if Self::agrees(pattern, other, size, dx, dy) {
+                            allowed[index / 64] |= 1 << (index % 64);
+                        }
+                    }
+                    allowed
+                })
+                .collect()
+        });
+
+        Self { size, tiles, cells, weights, compatible }
+    }
+
+    /// Whether `other`, placed at offset `(dx, dy)` from `pattern`, agrees with it where they
+    /// overlap
+    fn agrees(pattern: &[u8], other: &[u8], size: usize, dx: i32, dy: i32) -> bool {
+        let size = size as i32;
+        (0..size).all(|y| {
+            (0..size).all(|x| {
+                let (ox, oy) = (x - dx, y - dy);
+                let outside = ox < 0 || oy < 0 || ox >= size || oy >= size;
+                outside || pattern[(y * size + x) as usize] == other[(oy * size + ox) as usize]
+            })
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Pick one of the `possible` patterns, weighted by how often it appears in the samples
+    fn choose(&self, possible: &[u64], rng: &mut fastrand::Rng) -> usize {
+        let candidates: Vec<usize> = bits(possible).collect();
+        let total: f32 = candidates.iter().map(|&pattern| self.weights[pattern]).sum();
+
+        let mut roll = rng.f32() * total;
+        for &pattern in &candidates {
+            roll -= self.weights[pattern];
+            if roll <= 0.0 {
+                return pattern;
+            }
+        }
+
+        candidates[candidates.len() - 1]
+    }
+}
+
+/// The patterns still possible at every cell of the output, stored as bit sets
+struct Wave {
+    width: usize,
+    height: usize,
+    /// Words of each cell's bit set
+    words: usize,
+    bits: Vec<u64>,
+    /// Cells changed since the start, along with their previous bit sets, so changes can be undone
+    trail_cells: Vec<usize>,
+    trail_bits: Vec<u64>,
+}
+
+impl Wave {
+    /// Create a wave where every pattern is possible everywhere
+    fn new(width: usize, height: usize, pattern_count: usize) -> Self {
+        let words = pattern_count.div_ceil(64);
+        let mut all = vec![u64::MAX; words];
+        let unused_bits = words * 64 - pattern_count;
+        if unused_bits > 0 {
+            all[words - 1] = u64::MAX >> unused_bits;
+        }
+
+        Self {
+            width,
+            height,
+            words,
+            bits: all.repeat(width * height),
+            trail_cells: Vec::new(),
+            trail_bits: Vec::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn position(&self, cell: usize) -> (i32, i32) {
+        ((cell % self.width) as i32, (cell / self.width) as i32)
+    }
+
+    fn patterns(&self, cell: usize) -> &[u64] {
+        &self.bits[cell * self.words..(cell + 1) * self.words]
+    }
+
+    fn count(&self, cell: usize) -> u32 {
+        self.patterns(cell).iter().map(|word| word.count_ones()).sum()
+    }
+
+    fn first_pattern(&self, cell: usize) -> Option<usize> {
+        bits(self.patterns(cell)).next()
+    }
+
+    fn trail_len(&self) -> usize {
+        self.trail_cells.len()
+    }
+
+    /// Replace the patterns of `cell`, remembering the old ones
+    fn set(&mut self, cell: usize, patterns: &[u64]) {
+        let range = cell * self.words..(cell + 1) * self.words;
+        self.trail_cells.push(cell);
+        self.trail_bits.extend_from_slice(&self.bits[range.clone()]);
+        self.bits[range].copy_from_slice(patterns);
+    }
+
+    /// Undo every change made after the trail was `trail_len` long
+    fn undo(&mut self, trail_len: usize) {
+        while self.trail_cells.len() > trail_len {
+            let Some(cell) = self.trail_cells.pop() else {
+                break;
+            };
+            let old = self.trail_bits.split_off(self.trail_bits.len() - self.words);
+            self.bits[cell * self.words..(cell + 1) * self.words].copy_from_slice(&old);
+        }
+    }
+
+    /// The undecided cell with the fewest possible patterns, ties broken at random, or None if
+    /// every cell is decided
+    fn most_constrained(&self, rng: &mut fastrand::Rng) -> Option<usize> {
+        (0..self.len())
+            .map(|cell| (cell, self.count(cell)))
+            .filter(|&(_, count)| count > 1)
+            .map(|(cell, count)| (cell, count as f32 + rng.f32() * 0.5))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(cell, _)| cell)
+    }
+
+    /// Remove the patterns that no longer agree with their neighbors, starting from `cell`.
+    /// Returns false if a cell is left without any pattern.
+    fn propagate(&mut self, patterns: &Patterns, cell: usize) -> bool {
+        let mut stack = vec![cell];
+        let mut allowed = vec![0; self.words];
+        let mut narrowed = vec![0; self.words];
+
+        while let Some(cell) = stack.pop() {
+            let (x, y) = self.position(cell);
+
+            for (direction, (dx, dy)) in DIRECTIONS.into_iter().enumerate() {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= self.width as i32 || ny >= self.height as i32 {
+                    continue;
+                }
+                let neighbor = ny as usize * self.width + nx as usize;
+
+                allowed.fill(0);
+                for pattern in bits(self.patterns(cell)) {
+                    let compatible = &patterns.compatible[direction][pattern];
+                    for (word, compatible) in allowed.iter_mut().zip(compatible) {
+                        *word |= compatible;
+                    }
+                }
+
+                let current = self.patterns(neighbor);
+                for ((narrowed, word), allowed) in narrowed.iter_mut().zip(current).zip(&allowed) {
+                    *narrowed = word & allowed;
+                }
+                if narrowed == current {
+                    continue;
+                }
+                if narrowed.iter().all(|&word| word == 0) {
+                    return false;
+                }
+
+                self.set(neighbor, &narrowed);
+                stack.push(neighbor);
+            }
+        }
+
+        true
+    }
+}
+
+/// Indices of the set bits of a bit set
+fn bits(words: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    words.iter().enumerate().flat_map(|(index, &word)| {
+        let mut word = word;
+        std::iter::from_fn(move || {
+            (word != 0).then(|| {
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                index * 64 + bit
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "
+#########
+#...#...#
+#.......#
+#...#...#
+##.###.##
+#...#...#
+#.......#
+#########
+";
+
+    /// Whether every NxN window of `grid` is one of the learned patterns
+    fn only_holds_learned_patterns(grid: &Grid<TerrainType>, patterns: &Patterns) -> bool {
+        let size = patterns.size;
+        let tile = |position: (i32, i32)| {
+            patterns.tiles.iter().position(|tile| *tile == grid[position]).map(|index| index as u8)
+        };
+
+        (0..=grid.height() - size).all(|y| {
+            (0..=grid.width() - size).all(|x| {
+                let window: Option<Vec<u8>> = (0..size * size)
+                    .map(|index| tile(((x + index % size) as i32, (y + index / size) as i32)))
+                    .collect();
+                window.is_some_and(|window| patterns.cells.contains(&window))
+            })
+        })
+    }
+
+    #[test]
+    fn synthesized_levels_only_hold_learned_patterns() {
+        let generator = WfcGenerator::with_samples(30, 20, &[SAMPLE]).unwrap();
+        let patterns = Patterns::learn(&generator.samples, generator.pattern_size, generator.symmetry);
+
+        for seed in 0..10 {
+            let mut rng = fastrand::Rng::with_seed(seed);
+            let grid = generator.synthesize(&patterns, &mut rng);
+            let grid = grid.unwrap_or_else(|| panic!("seed {seed} failed to synthesize"));
+            assert!(only_holds_learned_patterns(&grid, &patterns), "seed {seed} made up a pattern");
+
+            let level = generator.finish(grid, &mut rng);
+            let level = level.unwrap_or_else(|| panic!("seed {seed} failed to finish"));
+            assert!(DungeonGenerator::unreachable_positions(&level).is_empty());
+            assert!(DungeonGenerator::find_terrain(&level, TerrainType::UpStairs).is_some());
+            assert!(DungeonGenerator::find_terrain(&level, TerrainType::DownStairs).is_some());
+        }
+    }
+
+    #[test]
+    fn contradictions_are_backtracked() {
+        // Floor is always chosen first, but nothing may neighbor it, so it has to be banned
+        let patterns = Patterns {
+            size: 1,
+            tiles: vec![TerrainType::Floor, TerrainType::Wall],
+            cells: vec![vec![0], vec![1]],
+            weights: vec![1.0, 0.0],
+            compatible: std::array::from_fn(|_| vec![vec![0b00], vec![0b10]]),
+        };
+        let mut generator = WfcGenerator { max_backtracks: 0, ..WfcGenerator::new(4, 3) };
+        assert!(generator.synthesize(&patterns, &mut fastrand::Rng::with_seed(1)).is_none());
+
+        generator.max_backtracks = 1;
+        let grid = generator.synthesize(&patterns, &mut fastrand::Rng::with_seed(1)).unwrap();
+        assert_eq!(grid.to_text(TerrainType::glyph), "####\n####\n####");
+    }
+
+    #[test]
+    fn generated_levels_are_connected_and_only_use_sampled_terrain() {
+        let generator = WfcGenerator::with_samples(30, 20, &[SAMPLE]).unwrap();
+        let mut rng = fastrand::Rng::with_seed(11);
+
+        for _ in 0..3 {
+            let grid = generator.generate(&mut rng);
+
+            assert!(DungeonGenerator::unreachable_positions(&grid).is_empty());
+            assert!(DungeonGenerator::find_terrain(&grid, TerrainType::DownStairs).is_some());
+            assert!(grid.iter().all(|terrain| !matches!(terrain, TerrainType::Door | TerrainType::OpenDoor)));
+        }
+    }
+}
@@ -7,6 +7,9 @@ pub mod systems;
 pub mod types;
 pub mod utils;
 
+#[cfg(test)]
+mod test_utils;
+
 mod model_constants;
 pub use self::model_constants::*;
 
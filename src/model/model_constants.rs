@@ -3,4 +3,9 @@ pub struct ModelConstants;
 impl ModelConstants {
     pub const MAP_WIDTH: usize = 40;
     pub const MAP_HEIGHT: usize = 30;
+
+    /// Time an action takes for an actor of normal speed
+    pub const BASE_ACTION_COST: u64 = 1000;
+    /// Speed of an actor that acts exactly once per base action cost
+    pub const NORMAL_SPEED: u64 = 100;
 }
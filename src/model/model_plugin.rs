@@ -1,9 +1,15 @@
 use bevy::prelude::*;
 
 use crate::model::{
-    components::{AwaitingInput, Description, PlayerTag, Position, Renderable, TerrainType, ViewShed},
-    resources::{CurrentMap, FovMap, SpawnPoint},
-    systems::{compute_fov, monsters_turn, process_turns, spawn_map, spawn_player},
+    components::{
+        AwaitingInput, CombatStats, Description, Health, MapLayer, PlayerTag, Position, Renderable,
+        TerrainType, ViewShed,
+    },
+    resources::{CurrentMap, DiagonalRule, Dungeon, FovMap, GameRng, SpawnPoint},
+    systems::{
+        compute_fov, monsters_turn, process_turns, schedule_new_actors, spawn_map, spawn_player,
+        unschedule_removed_actors, update_spatial_index, update_terrain_masks,
+    },
 };
 
 use super::resources::TurnQueue;
@@ -25,13 +31,17 @@ pub enum GameState {
 pub struct ModelPlugin;
 impl Plugin for ModelPlugin {
     fn build(&self, app: &mut App) {
+        app.register_type::<CombatStats>();
         app.register_type::<Description>();
+        app.register_type::<Health>();
+        app.register_type::<MapLayer>();
         app.register_type::<PlayerTag>();
         app.register_type::<Position>();
         app.register_type::<Renderable>();
         app.register_type::<TerrainType>();
         app.register_type::<ViewShed>();
         app.register_type::<SpawnPoint>();
+        app.register_type::<DiagonalRule>();
 
         app.init_state::<GameState>();
 
@@ -39,9 +49,20 @@ impl Plugin for ModelPlugin {
         app.init_resource::<CurrentMap>();
         app.init_resource::<FovMap>();
         app.init_resource::<SpawnPoint>();
+        app.init_resource::<DiagonalRule>();
+        app.init_resource::<Dungeon>();
+        app.init_resource::<GameRng>();
 
-        app.add_systems(Startup, (spawn_map, spawn_player, compute_fov, process_turns).chain());
+        app.add_systems(
+            Startup,
+            (spawn_map, spawn_player, update_spatial_index, compute_fov, process_turns).chain(),
+        );
 
+        app.add_systems(
+            Update,
+            (update_spatial_index.before(process_turns).before(monsters_turn), update_terrain_masks),
+        );
+        app.add_systems(Update, (schedule_new_actors, unschedule_removed_actors));
         app.add_systems(Update, process_turns.run_if(in_state(GameState::ProcessTurns)));
         app.add_systems(Update, monsters_turn.run_if(in_state(GameState::MonstersTurn)));
         app.add_systems(OnExit(GameState::ProcessTurns), compute_fov);
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+use crate::model::{components::Position, resources::Map};
+
+/// Decides when actors may step diagonally
+#[derive(Resource, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+pub enum DiagonalRule {
+    /// Diagonal steps are always allowed, even between two walls
+    Always,
+    /// Diagonal steps are allowed unless either adjacent orthogonal tile is blocked
+    #[default]
+    NoCornerCutting,
+    /// Only cardinal steps are allowed
+    Never,
+}
+
+impl DiagonalRule {
+    /// Returns true if a step of `delta` from `from` is allowed by this rule.
+    /// Cardinal steps are always allowed.
+    pub fn allows(&self, map: &Map, from: Position, delta: IVec2) -> bool {
+        if delta.x == 0 || delta.y == 0 {
+            return true;
+        }
+
+        match self {
+            DiagonalRule::Always => true,
+            DiagonalRule::NoCornerCutting => {
+                map.is_walkable(from + IVec2::new(delta.x, 0))
+                    && map.is_walkable(from + IVec2::new(0, delta.y))
+            }
+            DiagonalRule::Never => false,
+        }
+    }
+}
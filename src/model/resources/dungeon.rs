@@ -0,0 +1,70 @@
+use bevy::{prelude::*, utils::HashMap};
+use brtk::prelude::*;
+
+use crate::model::{
+    components::{CombatStats, Health, Position, TerrainType, TurnActor},
+    resources::FovMap,
+};
+
+/// The levels of the dungeon the player has visited, and the depth of the current one
+#[derive(Resource)]
+pub struct Dungeon {
+    depth: u32,
+    levels: HashMap<u32, StoredLevel>,
+}
+
+impl Default for Dungeon {
+    fn default() -> Self {
+        Self { depth: 1, levels: HashMap::new() }
+    }
+}
+
+impl Dungeon {
+    /// Depth of the current level, starting at 1 for the first level
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    pub fn set_depth(&mut self, depth: u32) {
+        self.depth = depth;
+    }
+
+    /// Keep a level around so it can be restored when the player comes back
+    pub fn store_level(&mut self, depth: u32, level: StoredLevel) {
+        self.levels.insert(depth, level);
+    }
+
+    /// Take a previously stored level out of the dungeon
+    pub fn take_level(&mut self, depth: u32) -> Option<StoredLevel> {
+        self.levels.remove(&depth)
+    }
+
+    pub fn has_level(&self, depth: u32) -> bool {
+        self.levels.contains_key(&depth)
+    }
+}
+
+/// Everything needed to restore a level exactly as the player left it
+pub struct StoredLevel {
+    pub terrain: Grid<TerrainType>,
+    pub fov_map: FovMap,
+    pub monsters: Vec<StoredMonster>,
+    pub items: Vec<StoredItem>,
+}
+
+/// A monster of a level the player is not on, with the state it had when the player left
+pub struct StoredMonster {
+    pub position: Position,
+    pub health: Option<Health>,
+    pub combat_stats: Option<CombatStats>,
+    /// Speed, queued actions and next turn time
+    pub turn_actor: Option<TurnActor>,
+    pub dead: bool,
+}
+
+/// An item lying on a level the player is not on
+pub struct StoredItem {
+    pub position: Position,
+    pub name: String,
+    pub glyph: char,
+}
@@ -1,19 +1,17 @@
 use crate::model::{
-    components::{Position, TerrainType},
+    components::Position,
     resources::Map,
     ModelConstants,
 };
 use bevy::prelude::*;
-use bitvec::prelude::*;
+use brtk::grid::BitGrid;
 
 /// Field of view map using bit-level storage for memory efficiency.
-/// This implementation uses the BitVec crate to store boolean values as individual bits.
-#[derive(Resource)]
+/// This implementation uses `BitGrid` to store boolean values as individual bits.
+#[derive(Resource, Clone)]
 pub struct FovMap {
-    width: usize,
-    height: usize,
-    revealed: BitVec,
-    visible: BitVec,
+    revealed: BitGrid,
+    visible: BitGrid,
 }
 
 impl FromWorld for FovMap {
@@ -25,46 +23,29 @@ impl FromWorld for FovMap {
 
 impl FovMap {
     pub fn new(width: usize, height: usize) -> Self {
-        let size = width * height;
-        Self { width, height, revealed: bitvec![0; size], visible: bitvec![0; size] }
-    }
-
-    /// Converts 2D coordinates to a 1D index
-    fn coords_to_index(&self, x: i32, y: i32) -> Option<usize> {
-        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
-            return None;
-        }
-        Some(y as usize * self.width + x as usize)
+        let size = (width, height);
+        Self { revealed: BitGrid::new(size), visible: BitGrid::new(size) }
     }
 
     /// Checks if a position is revealed (has been seen before)
     pub fn is_revealed(&self, pos: Position) -> bool {
-        let (x, y) = pos.into();
-        self.coords_to_index(x, y).map(|idx| self.revealed[idx]).unwrap_or(false)
+        self.revealed.is_set(pos)
     }
 
     /// Checks if a position is currently visible
     pub fn is_visible(&self, pos: Position) -> bool {
-        let (x, y) = pos.into();
-        self.coords_to_index(x, y).map(|idx| self.visible[idx]).unwrap_or(false)
+        self.visible.is_set(pos)
     }
 
     /// Marks a position as revealed
     pub fn set_revealed(&mut self, pos: Position, value: bool) {
-        let (x, y) = pos.into();
-        if let Some(idx) = self.coords_to_index(x, y) {
-            self.revealed.set(idx, value);
-        }
+        self.revealed.set(pos, value);
     }
 
     /// Marks a position as visible
     pub fn set_visible(&mut self, pos: Position, value: bool) {
-        let (x, y) = pos.into();
-        if let Some(idx) = self.coords_to_index(x, y) {
-            self.visible.set(idx, value);
-            if value {
-                self.revealed.set(idx, true);
-            }
+        if self.visible.set(pos, value) && value {
+            self.revealed.set(pos, true);
         }
     }
 
@@ -74,7 +55,7 @@ impl FovMap {
     }
 
     /// Updates the FOV for an entity at the given position with the given radius
-    pub fn compute_fov(&mut self, q_terrain: &Query<&TerrainType>, map: &Map, origin: Position, radius: i32) {
+    pub fn compute_fov(&mut self, map: &Map, origin: Position, radius: i32) {
         self.clear_visibility();
 
         // Always mark the origin as visible
@@ -82,7 +63,7 @@ impl FovMap {
 
         // Process all 8 octants using shadowcasting
         for octant in 0..8 {
-            self.cast_light(q_terrain, map, origin, radius, 1, 0.0, 1.0, octant);
+            self.cast_light(map, origin, radius, 1, 0.0, 1.0, octant);
         }
     }
 
@@ -108,13 +89,12 @@ impl FovMap {
     /// Recursive function to calculate FOV for a single octant using shadowcasting
     fn cast_light(
         &mut self,
-        q_terrain: &Query<&TerrainType>,
         map: &Map,
         origin: Position,
         radius: i32,
         row: i32,
         mut start_slope: f32,
-        mut end_slope: f32,
+        end_slope: f32,
         octant: i32,
     ) {
         // If the start slope is greater than the end slope, we're done
@@ -161,11 +141,7 @@ impl FovMap {
             self.set_visible(pos, true);
 
             // Determine if this tile blocks vision
-            let is_blocking = map
-                .get_terrain(pos)
-                .and_then(|entity| q_terrain.get(entity).ok())
-                .map(|terrain| terrain.blocks_vision())
-                .unwrap_or(false);
+            let is_blocking = map.is_opaque(pos);
 
             if was_blocked {
                 // We were previously blocked
@@ -183,35 +159,62 @@ impl FovMap {
             } else {
                 // We weren't previously blocked
                 if is_blocking {
-                    // This is a wall, calculate the end slope for the next scan
-                    let new_end_slope = (col as f32 + 0.5) / row as f32;
-
-                    // Recursively scan up to this wall
-                    if col < max_col {
-                        // Only if not the last column
-                        self.cast_light(
-                            q_terrain,
-                            map,
-                            origin,
-                            radius,
-                            row + 1,
-                            start_slope,
-                            new_end_slope,
-                            octant,
-                        );
-                    }
+                    // This is a wall, the next rows are only lit up to its near edge
+                    let new_end_slope = (col as f32 - 0.5) / row as f32;
+
+                    // Recursively scan the open cells before this wall
+                    self.cast_light(map, origin, radius, row + 1, start_slope, new_end_slope, octant);
 
                     // Mark that we're now blocked
                     was_blocked = true;
-                    // Adjust end slope for scanning the rest of this row
-                    end_slope = new_end_slope;
                 }
             }
         }
 
         // If we reach the end without being blocked, continue to the next row
         if !was_blocked {
-            self.cast_light(q_terrain, map, origin, radius, row + 1, start_slope, end_slope, octant);
+            self.cast_light(map, origin, radius, row + 1, start_slope, end_slope, octant);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::components::TerrainType;
+
+    fn open_room(world: &mut World, size: (usize, usize)) -> Map {
+        let map = Map::new(&mut world.commands(), size);
+        world.flush();
+        map
+    }
+
+    #[test]
+    fn open_rooms_are_fully_visible() {
+        let mut world = World::new();
+        let map = open_room(&mut world, (9, 7));
+        let mut fov = FovMap::new(9, 7);
+        fov.compute_fov(&map, Position::new(4, 3), 10);
+
+        for y in 0..7 {
+            for x in 0..9 {
+                assert!(fov.is_visible(Position::new(x, y)), "({x}, {y}) should be visible");
+            }
+        }
+    }
+
+    #[test]
+    fn walls_cast_shadows() {
+        let mut world = World::new();
+        let mut map = open_room(&mut world, (9, 7));
+        map.update_masks(Position::new(5, 3), &TerrainType::Wall);
+        let mut fov = FovMap::new(9, 7);
+        fov.compute_fov(&map, Position::new(4, 3), 10);
+
+        assert!(fov.is_visible(Position::new(5, 3)));
+        assert!(!fov.is_visible(Position::new(6, 3)));
+        assert!(!fov.is_visible(Position::new(7, 3)));
+        assert!(fov.is_visible(Position::new(6, 2)));
+        assert!(fov.is_visible(Position::new(3, 3)));
+    }
+}
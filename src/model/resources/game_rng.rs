@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::AppSettings;
+
+/// The single source of randomness for the model.
+///
+/// Every purpose draws from its own stream derived from the seed, so adding a random roll to
+/// combat does not change the maps that get generated. The same seed and the same inputs always
+/// produce the same game.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    map_gen: fastrand::Rng,
+    ai: fastrand::Rng,
+    combat: fastrand::Rng,
+}
+
+impl FromWorld for GameRng {
+    fn from_world(world: &mut World) -> Self {
+        let seed = world
+            .get_resource::<AppSettings>()
+            .and_then(AppSettings::seed)
+            .unwrap_or_else(|| fastrand::u64(..));
+        Self::new(seed)
+    }
+}
+
+impl GameRng {
+    const MAP_GEN_STREAM: u64 = 1;
+    const AI_STREAM: u64 = 2;
+    const COMBAT_STREAM: u64 = 3;
+
+    pub fn new(seed: u64) -> Self {
+        log::info!("Game seed: {}", seed);
+
+        Self {
+            seed,
+            map_gen: fastrand::Rng::with_seed(Self::derive_seed(seed, Self::MAP_GEN_STREAM)),
+            ai: fastrand::Rng::with_seed(Self::derive_seed(seed, Self::AI_STREAM)),
+            combat: fastrand::Rng::with_seed(Self::derive_seed(seed, Self::COMBAT_STREAM)),
+        }
+    }
+
+    /// The seed the game was started with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Randomness for dungeon generation and placing entities on new levels
+    pub fn map_gen(&mut self) -> &mut fastrand::Rng {
+        &mut self.map_gen
+    }
+
+    /// Randomness for monster decisions
+    pub fn ai(&mut self) -> &mut fastrand::Rng {
+        &mut self.ai
+    }
+
+    /// Randomness for attacks and damage
+    pub fn combat(&mut self) -> &mut fastrand::Rng {
+        &mut self.combat
+    }
+
+    // Mixes the stream id into the seed with SplitMix64, so streams of nearby seeds do not overlap
+    fn derive_seed(seed: u64, stream: u64) -> u64 {
+        let mut z = seed.wrapping_add(stream.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{components::TerrainType, generation::generator_for_depth, ModelConstants};
+
+    fn first_level(seed: u64) -> String {
+        let mut rng = GameRng::new(seed);
+        let mut generator = generator_for_depth(1, ModelConstants::MAP_WIDTH, ModelConstants::MAP_HEIGHT);
+        generator.generate(rng.map_gen()).to_text(TerrainType::glyph)
+    }
+
+    #[test]
+    fn same_seed_generates_the_same_first_level() {
+        assert_eq!(first_level(42), first_level(42));
+        assert_ne!(first_level(42), first_level(43));
+    }
+
+    #[test]
+    fn streams_are_independent() {
+        let mut a = GameRng::new(42);
+        let mut b = GameRng::new(42);
+        for _ in 0..10 {
+            a.ai().u64(..);
+            a.combat().u64(..);
+        }
+        assert_eq!(a.map_gen().u64(..), b.map_gen().u64(..));
+
+        let mut c = GameRng::new(42);
+        for _ in 0..10 {
+            c.combat().u64(..);
+        }
+        assert_eq!(b.ai().u64(..), c.ai().u64(..));
+
+        let mut d = GameRng::new(42);
+        let first = [d.map_gen().u64(..), d.ai().u64(..), d.combat().u64(..)];
+        assert!(first[0] != first[1] && first[1] != first[2] && first[0] != first[2]);
+    }
+}
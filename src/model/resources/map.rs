@@ -1,8 +1,9 @@
-use bevy::{prelude::*, utils::HashMap};
+use bevy::prelude::*;
 use brtk::prelude::*;
 
 use crate::model::{
-    components::{Description, Position, TerrainType},
+    components::{Description, MapLayer, Position, TerrainType},
+    resources::SpatialIndex,
     ModelConstants,
 };
 
@@ -11,45 +12,71 @@ pub struct Map {
     pub size: (usize, usize),
 
     pub terrain: Grid<Entity>,
-    pub actors: HashMap<Position, Entity>,
+    #[reflect(ignore)]
+    pub entities: SpatialIndex,
+
+    /// Tiles that actors can walk onto, mirrored from the terrain
+    #[reflect(ignore)]
+    walkable: BitGrid,
+    /// Tiles that block vision, mirrored from the terrain
+    #[reflect(ignore)]
+    opaque: BitGrid,
 }
 
 impl FromWorld for Map {
     fn from_world(world: &mut World) -> Self {
         let size = (ModelConstants::MAP_WIDTH, ModelConstants::MAP_HEIGHT);
+        let terrain_types = Self::default_terrain(size);
+        let terrain = terrain_types.map_with_positions(|(x, y), tile_type| {
+            let tile_description = Description::new(if tile_type.is_impassable() { "Wall" } else { "Floor" });
+            world.spawn((tile_type.clone(), tile_description, Position::new(x, y))).id()
+        });
 
-        Self {
-            size,
-            actors: HashMap::new(),
-            terrain: Grid::new_fn(size, |_index, (x, y)| {
-                let (tile_type, tile_description) = if x == 0 || y == 0 || x == size.0 - 1 || y == size.1 - 1
-                {
-                    (TerrainType::Wall, Description::new("Wall"))
-                } else {
-                    (TerrainType::Floor, Description::new("Floor"))
-                };
-                world.spawn((tile_type, tile_description, Position::new(x as i32, y as i32))).id()
-            }),
-        }
+        Self::from_parts(size, terrain, &terrain_types)
     }
 }
 
 impl Map {
     pub fn new(commands: &mut Commands, size: (usize, usize)) -> Self {
-        let terrain = Grid::new_fn(size, |_index, (x, y)| {
-            let (tile_type, tile_description) = if x == 0 || y == 0 || x == size.0 - 1 || y == size.1 - 1 {
-                (TerrainType::Wall, Description::new("Wall"))
-            } else {
-                (TerrainType::Floor, Description::new("Floor"))
-            };
-            commands.spawn((tile_type, tile_description, Position::new(x as i32, y as i32))).id()
+        let terrain_types = Self::default_terrain(size);
+        let terrain = terrain_types.map_with_positions(|(x, y), tile_type| {
+            let tile_description = Description::new(if tile_type.is_impassable() { "Wall" } else { "Floor" });
+            commands.spawn((tile_type.clone(), tile_description, Position::new(x, y))).id()
         });
 
-        Self { size, terrain, actors: HashMap::new() }
+        Self::from_parts(size, terrain, &terrain_types)
+    }
+
+    /// Build a map from already spawned terrain entities and the terrain types they hold
+    pub fn from_parts(
+        size: (usize, usize),
+        terrain: Grid<Entity>,
+        terrain_types: &Grid<TerrainType>,
+    ) -> Self {
+        let mut map = Self {
+            size,
+            terrain,
+            entities: SpatialIndex::default(),
+            walkable: BitGrid::new(size),
+            opaque: BitGrid::new(size),
+        };
+        map.rebuild_masks(terrain_types);
+        map
+    }
+
+    // A walled rectangle of floor
+    fn default_terrain(size: (usize, usize)) -> Grid<TerrainType> {
+        Grid::new_fn(size, |_index, (x, y)| {
+            if x == 0 || y == 0 || x == size.0 - 1 || y == size.1 - 1 {
+                TerrainType::Wall
+            } else {
+                TerrainType::Floor
+            }
+        })
     }
 
     pub fn pos_to_idx(&self, position: Position) -> usize {
-        self.terrain.position_to_index_unchecked(position.into())
+        self.terrain.position_to_index_unchecked(position)
     }
 
     pub fn idx_to_pos(&self, idx: usize) -> Option<(i32, i32)> {
@@ -57,24 +84,47 @@ impl Map {
     }
 
     pub fn get_terrain(&self, position: Position) -> Option<Entity> {
-        self.terrain.get(position.into()).copied()
+        self.terrain.get(position).copied()
     }
 
     pub fn get_actor(&self, position: Position) -> Option<Entity> {
-        self.actors.get(&position).copied()
+        self.entities.first_at_layer(position, MapLayer::Actor)
+    }
+
+    /// Returns true if actors can walk onto the tile at `position`
+    pub fn is_walkable(&self, position: Position) -> bool {
+        self.walkable.is_set(position)
+    }
+
+    /// Returns true if the tile at `position` blocks vision
+    pub fn is_opaque(&self, position: Position) -> bool {
+        self.opaque.is_set(position)
+    }
+
+    /// Tiles that actors can walk onto, for pathfinding and other bulk queries
+    pub fn walkable(&self) -> &BitGrid {
+        &self.walkable
+    }
+
+    /// Tiles that block vision, for FOV and other bulk queries
+    pub fn opaque(&self) -> &BitGrid {
+        &self.opaque
+    }
+
+    /// Refresh the cached masks for a single tile after its terrain changed
+    pub fn update_masks(&mut self, position: Position, terrain_type: &TerrainType) {
+        self.walkable.set(position, !terrain_type.blocks_movement());
+        self.opaque.set(position, terrain_type.blocks_vision());
     }
 
-    pub fn set_actor(&mut self, position: Position, actor: Option<Entity>) {
-        if let Some(actor) = actor {
-            self.actors.insert(position, actor);
-        } else {
-            self.actors.remove(&position);
-        }
+    /// Rebuild the cached masks from a whole terrain grid
+    pub fn rebuild_masks(&mut self, terrain_types: &Grid<TerrainType>) {
+        self.walkable = BitGrid::from_grid(terrain_types, |terrain_type| !terrain_type.blocks_movement());
+        self.opaque = BitGrid::from_grid(terrain_types, TerrainType::blocks_vision);
     }
 
     // Helper method to check if a position is in bounds
     pub fn in_bounds(&self, position: Position) -> bool {
-        let (x, y) = position.into();
-        x >= 0 && y >= 0 && x < self.size.0 as i32 && y < self.size.1 as i32
+        self.terrain.in_bounds(position)
     }
 }
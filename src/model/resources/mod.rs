@@ -1,6 +1,15 @@
 mod current_map;
 pub use self::current_map::*;
 
+mod dungeon;
+pub use self::dungeon::*;
+
+mod diagonal_rule;
+pub use self::diagonal_rule::*;
+
+mod game_rng;
+pub use self::game_rng::*;
+
 mod map;
 pub use self::map::*;
 
@@ -12,3 +21,6 @@ pub use self::fov_map::*;
 
 mod spawn_point;
 pub use self::spawn_point::*;
+
+mod spatial_index;
+pub use self::spatial_index::*;
@@ -0,0 +1,203 @@
+use bevy::{prelude::*, utils::HashMap};
+use brtk::prelude::*;
+use smallvec::SmallVec;
+
+use crate::model::components::{MapLayer, Position};
+
+/// Tracks which entities stand on each tile of the map, split by [`MapLayer`].
+///
+/// Entities are stored per cell, with a reverse lookup so an entity can be moved or removed
+/// without knowing where it was.
+#[derive(Default, Debug, Clone)]
+pub struct SpatialIndex {
+    cells: HashMap<Position, SmallVec<[(Entity, MapLayer); 4]>>,
+    locations: HashMap<Entity, (Position, MapLayer)>,
+}
+
+impl SpatialIndex {
+    /// Insert an entity at a position, moving it there if it is already indexed
+    pub fn insert(&mut self, entity: Entity, position: Position, layer: MapLayer) {
+        if self.locations.get(&entity) == Some(&(position, layer)) {
+            return;
+        }
+
+        self.remove(entity);
+        self.cells.entry(position).or_default().push((entity, layer));
+        self.locations.insert(entity, (position, layer));
+    }
+
+    /// Move an already indexed entity to a new position, keeping its layer.
+    /// Returns false if the entity is not indexed.
+    pub fn move_entity(&mut self, entity: Entity, position: Position) -> bool {
+        match self.locations.get(&entity) {
+            Some(&(_, layer)) => {
+                self.insert(entity, position, layer);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove an entity, returning where it was
+    pub fn remove(&mut self, entity: Entity) -> Option<(Position, MapLayer)> {
+        let (position, layer) = self.locations.remove(&entity)?;
+
+        if let Some(cell) = self.cells.get_mut(&position) {
+            cell.retain(|(other, _)| *other != entity);
+            if cell.is_empty() {
+                self.cells.remove(&position);
+            }
+        }
+
+        Some((position, layer))
+    }
+
+    /// Remove every entity
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.locations.clear();
+    }
+
+    /// Position and layer of an indexed entity
+    pub fn location(&self, entity: Entity) -> Option<(Position, MapLayer)> {
+        self.locations.get(&entity).copied()
+    }
+
+    /// Determine if an entity is indexed
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.locations.contains_key(&entity)
+    }
+
+    /// Number of indexed entities
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// Determine if no entity is indexed
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+
+    /// Iterate over every entity at a position, on any layer
+    pub fn entities_at(&self, position: Position) -> impl Iterator<Item = Entity> + '_ {
+        self.cells.get(&position).into_iter().flatten().map(|&(entity, _)| entity)
+    }
+
+    /// Iterate over every entity at a position on a single layer
+    pub fn entities_at_layer(
+        &self,
+        position: Position,
+        layer: MapLayer,
+    ) -> impl Iterator<Item = Entity> + '_ {
+        self.cells
+            .get(&position)
+            .into_iter()
+            .flatten()
+            .filter(move |&&(_, other)| other == layer)
+            .map(|&(entity, _)| entity)
+    }
+
+    /// The first entity at a position on a single layer
+    pub fn first_at_layer(&self, position: Position, layer: MapLayer) -> Option<Entity> {
+        self.entities_at_layer(position, layer).next()
+    }
+
+    /// Determine if a position holds any entity on a layer
+    pub fn is_occupied(&self, position: Position, layer: MapLayer) -> bool {
+        self.first_at_layer(position, layer).is_some()
+    }
+
+    /// Iterate over every entity within `radius` of `center`, with its position and layer
+    pub fn entities_in_radius(
+        &self,
+        center: Position,
+        radius: i32,
+    ) -> impl Iterator<Item = (Entity, Position, MapLayer)> + '_ {
+        circle(center.grid_position(), radius)
+            .flat_map(move |(x, y)| self.entities_in_cell(Position::new(x, y)))
+    }
+
+    /// Iterate over every entity inside of a rect, with its position and layer
+    pub fn entities_in_rect(
+        &self,
+        rect: GridRect,
+    ) -> impl Iterator<Item = (Entity, Position, MapLayer)> + '_ {
+        rect.positions().flat_map(move |(x, y)| self.entities_in_cell(Position::new(x, y)))
+    }
+
+    /// Iterate over every indexed entity, with its position and layer
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Position, MapLayer)> + '_ {
+        self.locations.iter().map(|(&entity, &(position, layer))| (entity, position, layer))
+    }
+
+    fn entities_in_cell(
+        &self,
+        position: Position,
+    ) -> impl Iterator<Item = (Entity, Position, MapLayer)> + '_ {
+        self.cells
+            .get(&position)
+            .into_iter()
+            .flatten()
+            .map(move |&(entity, layer)| (entity, position, layer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(index: &SpatialIndex, position: Position) -> Vec<Entity> {
+        index.entities_at(position).collect()
+    }
+
+    #[test]
+    fn insert_indexes_entities_by_position_and_layer() {
+        let mut index = SpatialIndex::default();
+        let (orc, sword) = (Entity::from_raw(1), Entity::from_raw(2));
+        index.insert(orc, Position::new(2, 3), MapLayer::Actor);
+        index.insert(sword, Position::new(2, 3), MapLayer::Item);
+
+        assert_eq!(index.len(), 2);
+        assert_eq!(at(&index, Position::new(2, 3)), vec![orc, sword]);
+        assert_eq!(index.first_at_layer(Position::new(2, 3), MapLayer::Item), Some(sword));
+        assert!(index.is_occupied(Position::new(2, 3), MapLayer::Actor));
+        assert!(!index.is_occupied(Position::new(2, 3), MapLayer::Feature));
+        assert_eq!(index.location(orc), Some((Position::new(2, 3), MapLayer::Actor)));
+
+        // Inserting again moves the entity instead of duplicating it
+        index.insert(orc, Position::new(4, 3), MapLayer::Actor);
+        assert_eq!(index.len(), 2);
+        assert_eq!(at(&index, Position::new(2, 3)), vec![sword]);
+        assert_eq!(at(&index, Position::new(4, 3)), vec![orc]);
+    }
+
+    #[test]
+    fn move_entity_keeps_the_layer() {
+        let mut index = SpatialIndex::default();
+        let sword = Entity::from_raw(2);
+        index.insert(sword, Position::new(0, 0), MapLayer::Item);
+
+        assert!(index.move_entity(sword, Position::new(1, 0)));
+        assert_eq!(index.location(sword), Some((Position::new(1, 0), MapLayer::Item)));
+        assert!(at(&index, Position::new(0, 0)).is_empty());
+
+        assert!(!index.move_entity(Entity::from_raw(9), Position::new(1, 0)));
+        assert!(!index.contains(Entity::from_raw(9)));
+    }
+
+    #[test]
+    fn remove_forgets_the_entity() {
+        let mut index = SpatialIndex::default();
+        let (orc, goblin) = (Entity::from_raw(1), Entity::from_raw(3));
+        index.insert(orc, Position::new(5, 5), MapLayer::Actor);
+        index.insert(goblin, Position::new(5, 6), MapLayer::Actor);
+
+        assert_eq!(index.remove(orc), Some((Position::new(5, 5), MapLayer::Actor)));
+        assert_eq!(index.remove(orc), None);
+        assert!(at(&index, Position::new(5, 5)).is_empty());
+        assert_eq!(index.entities_in_radius(Position::new(5, 5), 1).count(), 1);
+
+        index.clear();
+        assert!(index.is_empty());
+    }
+}
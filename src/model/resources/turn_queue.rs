@@ -2,7 +2,7 @@ use std::{cmp::Reverse, collections::BinaryHeap};
 
 use bevy::prelude::*;
 
-use crate::model::components::{DeadTag, TurnActor};
+use crate::model::components::{DeadTag, Health, TurnActor};
 
 #[derive(Resource, Default)]
 pub struct TurnQueue {
@@ -46,6 +46,11 @@ impl TurnQueue {
         self.turn_queue.peek().map(|Reverse((time, entity))| (*entity, *time))
     }
 
+    // Remove every scheduled turn of an entity
+    pub fn remove(&mut self, entity: Entity) {
+        self.turn_queue.retain(|Reverse((_, e))| *e != entity);
+    }
+
     // Check if an entity's turn is scheduled
     pub fn is_scheduled(&self, entity: Entity) -> bool {
         self.turn_queue.iter().any(|Reverse((_, e))| *e == entity)
@@ -154,11 +159,11 @@ impl TurnQueue {
         }
 
         // Check for health <= 0 (if Health component exists)
-        // if let Some(health) = world.entity(entity).get::<Health>() {
-        //     if health.current <= 0 {
-        //         return false;
-        //     }
-        // }
+        if let Some(health) = world.entity(entity).get::<Health>() {
+            if health.is_dead() {
+                return false;
+            }
+        }
 
         true
     }
@@ -7,5 +7,14 @@ pub use self::spawn_map::*;
 mod spawn_player;
 pub use self::spawn_player::*;
 
+mod spatial_index;
+pub use self::spatial_index::*;
+
+mod turn_scheduling;
+pub use self::turn_scheduling::*;
+
+mod terrain_masks;
+pub use self::terrain_masks::*;
+
 mod fov;
 pub use self::fov::compute_fov;
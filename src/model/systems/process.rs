@@ -1,10 +1,11 @@
 use bevy::{ecs::system::SystemState, prelude::*};
 
 use crate::model::{
-    actions::WalkBuilder,
-    components::{AITag, AwaitingInput, DeadTag, PlayerTag, Position, TerrainType, TurnActor},
-    resources::{CurrentMap, TurnQueue},
+    actions::{WaitBuilder, WalkBuilder},
+    components::{AITag, AwaitingInput, DeadTag, PlayerTag, Position, TurnActor},
+    resources::{CurrentMap, DiagonalRule, GameRng, TurnQueue},
     types::{GameActionBuilder, MoveDirection},
+    utils::LevelChange,
     GameState,
 };
 
@@ -49,33 +50,67 @@ pub fn process_turns(world: &mut World) {
                 log::info!("Player is awaiting input: {:?}", entity);
                 next_state.set(GameState::PlayerTurn);
                 world.entity_mut(entity).insert(AwaitingInput);
-                turn_queue.schedule_turn(entity, time);
+                schedule_turn(world, &mut turn_queue, entity, time);
                 return;
             }
 
             let Some(action) = actor.next_action() else {
+                // Let the monsters decide on their actions before processing this turn again
                 log::info!("No action for entity: {:?}. Rescheduling turn.", entity);
-                turn_queue.schedule_turn(entity, time);
+                next_state.set(GameState::MonstersTurn);
+                schedule_turn(world, &mut turn_queue, entity, time);
                 return;
             };
 
             // Get next action and drop turn_queue borrow temporarily
             match action.perform(world) {
-                Ok(d_time) => turn_queue.schedule_turn(entity, time + d_time),
+                Ok(d_time) => schedule_turn(world, &mut turn_queue, entity, time + d_time),
                 Err(e) => {
                     log::error!("Failed to perform action: {:?}", e);
 
                     if is_player {
-                        turn_queue.schedule_turn(entity, time);
+                        schedule_turn(world, &mut turn_queue, entity, time);
                     } else {
-                        turn_queue.schedule_turn(entity, time + 100);
+                        schedule_turn(world, &mut turn_queue, entity, time + 100);
                     }
                 }
             }
+
+            // The action took the player to another level
+            if let Some(level_change) = world.remove_resource::<LevelChange>() {
+                schedule_level_change(world, &mut turn_queue, level_change, time);
+            }
         }
     });
 }
 
+// Schedules the next turn of an actor, keeping `next_turn_time` in sync so the time is kept when
+// the actor is stored with its level
+fn schedule_turn(world: &mut World, turn_queue: &mut TurnQueue, entity: Entity, time: u64) {
+    turn_queue.schedule_turn(entity, time);
+    if let Some(mut actor) = world.get_mut::<TurnActor>(entity) {
+        actor.next_turn_time = time;
+    }
+}
+
+// Drops the turns of the actors despawned by a level change, and schedules the actors it spawned
+// at the time they were left with, or now if that time has passed
+fn schedule_level_change(world: &mut World, turn_queue: &mut TurnQueue, level_change: LevelChange, now: u64) {
+    for entity in level_change.despawned {
+        turn_queue.remove(entity);
+    }
+
+    for entity in level_change.spawned {
+        let Some(actor) = world.get::<TurnActor>(entity).filter(|actor| actor.is_alive()) else {
+            continue;
+        };
+        if !turn_queue.is_scheduled(entity) {
+            let time = actor.next_turn_time.max(now);
+            schedule_turn(world, turn_queue, entity, time);
+        }
+    }
+}
+
 pub fn monsters_turn(world: &mut World) {
     log::info!("Monsters turn");
 
@@ -84,10 +119,14 @@ pub fn monsters_turn(world: &mut World) {
         Query<(Entity, &mut TurnActor), (With<AITag>, Without<PlayerTag>, Without<DeadTag>)>,
         Query<&Position>,
         Res<CurrentMap>,
-        Query<&TerrainType>,
+        Query<(), With<PlayerTag>>,
+        Option<Res<DiagonalRule>>,
+        ResMut<GameRng>,
     )> = SystemState::new(world);
 
-    let (mut next_state, mut ai_query, position_query, current_map, terrain_query) = state.get_mut(world);
+    let (mut next_state, mut ai_query, position_query, current_map, player_query, diagonal_rule, mut rng) =
+        state.get_mut(world);
+    let diagonal_rule = diagonal_rule.as_deref().copied().unwrap_or_default();
 
     for (entity, mut turn_actor) in &mut ai_query {
         // Skip entities that already have actions queued
@@ -97,24 +136,32 @@ pub fn monsters_turn(world: &mut World) {
 
         // Get the entity's current position
         if let Ok(position) = position_query.get(entity) {
-            // Try different directions in a random order
+            // Attack the player if it is adjacent, walking into it becomes an attack
+            let mut valid_direction = MoveDirection::all_directions().into_iter().find(|&direction| {
+                diagonal_rule.allows(&current_map, *position, direction.into())
+                    && current_map
+                        .get_actor(*position + direction)
+                        .is_some_and(|actor| player_query.contains(actor))
+            });
+
+            // Otherwise try different directions in a random order
             let directions = MoveDirection::all_directions();
-            let mut valid_direction = None;
 
             // Find a valid direction to move (one that leads to a walkable tile)
             for _ in 0..directions.len() {
-                let direction = MoveDirection::random_direction();
+                if valid_direction.is_some() {
+                    break;
+                }
+
+                let direction = MoveDirection::random_direction(rng.ai());
                 let new_position = *position + direction;
 
-                // Check if the new position is valid
-                if let Some(terrain_entity) = current_map.get_terrain(new_position) {
-                    if let Ok(terrain_type) = terrain_query.get(terrain_entity) {
-                        // Check if we can walk there
-                        if *terrain_type == TerrainType::Floor {
-                            valid_direction = Some(direction);
-                            break;
-                        }
-                    }
+                // Check if we can walk there
+                if diagonal_rule.allows(&current_map, *position, direction.into())
+                    && current_map.is_walkable(new_position)
+                    && current_map.get_actor(new_position).is_none()
+                {
+                    valid_direction = Some(direction);
                 }
             }
 
@@ -127,10 +174,69 @@ pub fn monsters_turn(world: &mut World) {
             } else {
                 // If no valid direction was found, just wait
                 log::debug!("AI entity {:?} has no valid move, waiting", entity);
-                // Here we could add a wait action if we had one
+                turn_actor.add_action(WaitBuilder::new().with_entity(entity).build());
             }
         }
     }
 
     next_state.set(GameState::ProcessTurns);
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::AssetPlugin;
+
+    use super::*;
+    use crate::model::{
+        actions::DescendBuilder,
+        components::{MapLayer, TerrainType},
+        resources::{Dungeon, FovMap},
+        test_utils::set_terrain,
+        utils::spawn_monster,
+    };
+
+    #[test]
+    fn descending_drops_the_turns_of_the_old_level() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+        app.init_asset::<Font>();
+        app.insert_resource(GameRng::new(7));
+        app.init_resource::<CurrentMap>();
+        app.init_resource::<FovMap>();
+        app.init_resource::<Dungeon>();
+        app.init_resource::<TurnQueue>();
+        app.init_resource::<NextState<GameState>>();
+
+        let world = app.world_mut();
+        world.flush();
+        let stairs = Position::new(1, 1);
+        set_terrain(world, stairs, TerrainType::DownStairs);
+
+        let player = world.spawn((PlayerTag, stairs, MapLayer::Actor, TurnActor::new(100))).id();
+        let descend = DescendBuilder::new().with_entity(player).build();
+        world.get_mut::<TurnActor>(player).unwrap().add_action(descend);
+        world.resource_mut::<CurrentMap>().entities.insert(player, stairs, MapLayer::Actor);
+
+        let mut state: SystemState<(Commands, Res<AssetServer>)> = SystemState::new(world);
+        let (mut commands, asset_server) = state.get_mut(world);
+        let monster = spawn_monster(&mut commands, &asset_server, Position::new(4, 2));
+        state.apply(world);
+
+        let mut turn_queue = world.resource_mut::<TurnQueue>();
+        turn_queue.schedule_turn(player, 0);
+        turn_queue.schedule_turn(monster, 50);
+
+        process_turns(world);
+
+        assert_eq!(world.resource::<Dungeon>().depth(), 2);
+        let turn_queue = world.resource::<TurnQueue>();
+        assert!(!turn_queue.is_scheduled(monster));
+        assert!(turn_queue.is_scheduled(player));
+
+        let mut q_monsters = world.query_filtered::<Entity, (With<AITag>, Without<DeadTag>)>();
+        let monsters: Vec<Entity> = q_monsters.iter(world).collect();
+        assert!(!monsters.is_empty());
+        let turn_queue = world.resource::<TurnQueue>();
+        assert!(monsters.iter().all(|&monster| turn_queue.is_scheduled(monster)));
+    }
+}
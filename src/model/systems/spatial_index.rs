@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+
+use crate::model::{
+    components::{MapLayer, Position},
+    resources::CurrentMap,
+};
+
+/// System that keeps the map's spatial index in sync with entity positions and layers
+pub fn update_spatial_index(
+    mut map: ResMut<CurrentMap>,
+    query: Query<(Entity, &Position, &MapLayer), Or<(Changed<Position>, Changed<MapLayer>)>>,
+    mut removed_positions: RemovedComponents<Position>,
+    mut removed_layers: RemovedComponents<MapLayer>,
+) {
+    for entity in removed_positions.read().chain(removed_layers.read()) {
+        map.entities.remove(entity);
+    }
+
+    for (entity, position, layer) in query.iter() {
+        map.entities.insert(entity, *position, *layer);
+    }
+}
@@ -1,17 +1,38 @@
 use bevy::prelude::*;
 
-use crate::model::{generation::DungeonGenerator, resources::CurrentMap, ModelConstants};
+use crate::model::{
+    components::TerrainType,
+    generation::{generator_for_depth, DungeonGenerator},
+    resources::{CurrentMap, Dungeon, GameRng},
+    utils::spawn_generated,
+    ModelConstants,
+};
 
-pub fn spawn_map(mut commands: Commands, mut current_map: ResMut<CurrentMap>) {
-    let mut rng = fastrand::Rng::new();
-    let mut generator = DungeonGenerator::new(ModelConstants::MAP_WIDTH, ModelConstants::MAP_HEIGHT);
+pub fn spawn_map(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut current_map: ResMut<CurrentMap>,
+    mut rng: ResMut<GameRng>,
+    dungeon: Res<Dungeon>,
+) {
+    let (width, height) = (ModelConstants::MAP_WIDTH, ModelConstants::MAP_HEIGHT);
+    let mut generator = generator_for_depth(dungeon.depth(), width, height);
 
     // Generate terrain types
-    let terrain_grid = generator.generate(&mut rng);
+    let terrain_grid = generator.generate(rng.map_gen());
+
+    // Rows are flipped so the dump reads the same way the map is drawn, with y pointing up
+    if log::log_enabled!(log::Level::Debug) {
+        log::debug!("Generated dungeon:\n{}", terrain_grid.flip_vertical().display_with(TerrainType::glyph));
+    }
 
     // Generate entities and update the map
-    let terrain_entities = generator.generate_entities(&mut commands, &terrain_grid);
+    let terrain_entities = DungeonGenerator::generate_entities(&mut commands, &terrain_grid);
 
     // Update the current map
     current_map.terrain = terrain_entities;
+    current_map.rebuild_masks(&terrain_grid);
+
+    // Spawn what the generator placed, such as the contents of vaults
+    spawn_generated(&mut commands, &asset_server, generator.spawns());
 }
@@ -1,29 +1,32 @@
 use bevy::prelude::*;
 
 use crate::model::{
-    components::{AITag, PlayerTag, Position, Renderable, TerrainType, TurnActor, ViewShed},
-    resources::{CurrentMap, SpawnPoint, TurnQueue},
-    utils::spawn_ascii_entity,
+    components::{
+        CombatStats, Health, MapLayer, PlayerTag, Position, Renderable, TerrainType, TurnActor, ViewShed,
+    },
+    resources::{CurrentMap, GameRng, SpawnPoint, TurnQueue},
+    utils::{spawn_ascii_entity, spawn_monster},
     ModelConstants,
 };
 
 pub fn spawn_player(
     mut commands: Commands,
-    mut current_map: ResMut<CurrentMap>,
+    current_map: Res<CurrentMap>,
     asset_server: Res<AssetServer>,
     mut turn_system: ResMut<TurnQueue>,
     terrain_query: Query<&TerrainType>,
     spawn_point: Option<Res<SpawnPoint>>,
+    mut rng: ResMut<GameRng>,
 ) {
     // Determine where to spawn the player
     let player_position = if let Some(spawn_point) = spawn_point {
         if let Some(pos) = spawn_point.player_spawn {
             pos
         } else {
-            find_valid_position(&current_map, &terrain_query)
+            find_valid_position(&current_map, &terrain_query, rng.map_gen())
         }
     } else {
-        find_valid_position(&current_map, &terrain_query)
+        find_valid_position(&current_map, &terrain_query, rng.map_gen())
     };
 
     // Spawn the player
@@ -38,26 +41,18 @@ pub fn spawn_player(
         1.0,
     );
 
-    commands.entity(player_id).insert((PlayerTag, TurnActor::new(100), ViewShed { radius: 8 }));
+    commands.entity(player_id).insert((
+        PlayerTag,
+        MapLayer::Actor,
+        TurnActor::new(100),
+        ViewShed { radius: 8 },
+        Health::new(30),
+        CombatStats::new(5, 2),
+    ));
 
     // Spawn an enemy at a random location
-    let actor_position = find_valid_position(&current_map, &terrain_query);
-    let actor_id = spawn_ascii_entity(
-        &mut commands,
-        &asset_server,
-        Some(actor_position),
-        Renderable {
-            glyph: 'E',
-            color: Color::srgb(1.0, 0.0, 0.0), // #ff0000
-        },
-        1.0,
-    );
-
-    commands.entity(actor_id).insert((AITag, TurnActor::new(120)));
-
-    // Set the player and actor on the map
-    current_map.set_actor(player_position, Some(player_id));
-    current_map.set_actor(actor_position, Some(actor_id));
+    let actor_position = find_valid_position(&current_map, &terrain_query, rng.map_gen());
+    let actor_id = spawn_monster(&mut commands, &asset_server, actor_position);
 
     // Schedule the player and actor to take turns
     let current_time = turn_system.current_time();
@@ -66,8 +61,11 @@ pub fn spawn_player(
 }
 
 // Helper function to find a valid floor position
-fn find_valid_position(current_map: &CurrentMap, terrain_query: &Query<&TerrainType>) -> Position {
-    let mut rng = fastrand::Rng::new();
+fn find_valid_position(
+    current_map: &CurrentMap,
+    terrain_query: &Query<&TerrainType>,
+    rng: &mut fastrand::Rng,
+) -> Position {
     let mut valid_positions = Vec::new();
 
     for y in 1..ModelConstants::MAP_HEIGHT - 1 {
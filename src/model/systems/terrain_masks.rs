@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+use crate::model::{
+    components::{Position, TerrainType},
+    resources::CurrentMap,
+};
+
+/// System that refreshes the map's walkable and opaque masks when terrain changes, such as a door
+/// opening
+pub fn update_terrain_masks(
+    mut map: ResMut<CurrentMap>,
+    query: Query<(Entity, &Position, &TerrainType), Changed<TerrainType>>,
+) {
+    for (entity, position, terrain_type) in query.iter() {
+        // Ignore terrain entities that are not part of the current map
+        if map.get_terrain(*position) == Some(entity) {
+            map.update_masks(*position, terrain_type);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+use crate::model::{
+    components::{DeadTag, TurnActor},
+    resources::TurnQueue,
+};
+
+/// System that schedules a first turn for actors spawned after startup, such as the monsters of
+/// a new level
+pub fn schedule_new_actors(
+    mut turn_queue: ResMut<TurnQueue>,
+    mut query: Query<(Entity, &mut TurnActor), (Added<TurnActor>, Without<DeadTag>)>,
+) {
+    let current_time = turn_queue.current_time();
+    for (entity, mut actor) in &mut query {
+        if !turn_queue.is_scheduled(entity) {
+            turn_queue.schedule_turn(entity, current_time);
+            actor.next_turn_time = current_time;
+        }
+    }
+}
+
+/// System that drops the scheduled turns of despawned actors
+pub fn unschedule_removed_actors(
+    mut turn_queue: ResMut<TurnQueue>,
+    mut removed: RemovedComponents<TurnActor>,
+) {
+    for entity in removed.read() {
+        turn_queue.remove(entity);
+    }
+}
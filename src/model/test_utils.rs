@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+use brtk::grid::Grid;
+
+use crate::model::{
+    components::{MapLayer, Position, TerrainType},
+    resources::{CurrentMap, Map},
+};
+
+/// Parse a level drawn with terrain glyphs the way the map is displayed, with north on the first
+/// line, as `Vault::from_text` reads its templates
+pub fn level(text: &str) -> Grid<TerrainType> {
+    Grid::from_text(text, TerrainType::from_glyph).expect("Test levels should parse").flip_vertical()
+}
+
+/// Draw a level with terrain glyphs, north first, the reverse of `level`
+pub fn level_text(grid: &Grid<TerrainType>) -> String {
+    grid.flip_vertical().to_text(TerrainType::glyph)
+}
+
+/// A world holding a 5x5 `CurrentMap`
+pub fn setup_world() -> World {
+    let mut world = World::new();
+    let map = Map::new(&mut world.commands(), (5, 5));
+    world.flush();
+    world.insert_resource(CurrentMap(map));
+    world
+}
+
+/// Spawn an actor at `position` and add it to the map's spatial index
+pub fn spawn_actor(world: &mut World, position: Position) -> Entity {
+    let entity = world.spawn((position, MapLayer::Actor)).id();
+    world.resource_mut::<CurrentMap>().entities.insert(entity, position, MapLayer::Actor);
+    entity
+}
+
+/// Change the terrain at `position`, keeping the map's walkable and opaque masks in sync
+pub fn set_terrain(world: &mut World, position: Position, terrain_type: TerrainType) {
+    let terrain_entity =
+        world.resource::<CurrentMap>().get_terrain(position).expect("Position should be on the map");
+    world.entity_mut(terrain_entity).insert(terrain_type.clone());
+    world.resource_mut::<CurrentMap>().update_masks(position, &terrain_type);
+}
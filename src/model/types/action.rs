@@ -1,12 +1,21 @@
 use bevy::prelude::*;
 
-use crate::model::types::{direction::MoveDirection, error::GameError};
+use crate::model::{
+    components::TurnActor,
+    types::{direction::MoveDirection, error::GameError},
+    ModelConstants,
+};
 
 #[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
 pub enum ActionType {
     Move(MoveDirection),
     Attack(Entity),
     Wait,
+    Rest,
+    OpenDoor,
+    CloseDoor,
+    Descend,
+    Ascend,
     // Other actions
 }
 
@@ -15,6 +24,17 @@ pub trait GameAction: Send + Sync + 'static + std::fmt::Debug {
     fn perform(&self, world: &mut World) -> Result<u64, GameError>;
 }
 
+/// Time an action with the given base cost takes for `entity`, scaled by its `TurnActor` speed.
+/// Entities without a `TurnActor` act at normal speed.
+pub fn action_cost(world: &World, entity: Entity, base_cost: u64) -> u64 {
+    world.get::<TurnActor>(entity).map_or(base_cost, |actor| actor.action_cost(base_cost))
+}
+
+/// Time a regular action takes for `entity`
+pub fn base_action_cost(world: &World, entity: Entity) -> u64 {
+    action_cost(world, entity, ModelConstants::BASE_ACTION_COST)
+}
+
 /// Builder trait for GameAction implementations
 pub trait GameActionBuilder: Send + Sync + 'static {
     /// The action type this builder creates
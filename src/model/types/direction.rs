@@ -4,33 +4,70 @@ use bevy::prelude::*;
 
 use crate::model::components::Position;
 
+/// A step to one of the eight neighboring tiles. North points to +y, matching the way the map is
+/// drawn.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MoveDirection {
     North,
     South,
     East,
     West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
 }
 
 impl MoveDirection {
-    pub const ALL_DIRECTIONS: [MoveDirection; 4] =
+    pub const CARDINAL_DIRECTIONS: [MoveDirection; 4] =
         [MoveDirection::North, MoveDirection::South, MoveDirection::East, MoveDirection::West];
 
+    pub const DIAGONAL_DIRECTIONS: [MoveDirection; 4] = [
+        MoveDirection::NorthEast,
+        MoveDirection::NorthWest,
+        MoveDirection::SouthEast,
+        MoveDirection::SouthWest,
+    ];
+
+    pub const ALL_DIRECTIONS: [MoveDirection; 8] = [
+        MoveDirection::North,
+        MoveDirection::South,
+        MoveDirection::East,
+        MoveDirection::West,
+        MoveDirection::NorthEast,
+        MoveDirection::NorthWest,
+        MoveDirection::SouthEast,
+        MoveDirection::SouthWest,
+    ];
+
     pub fn delta(&self) -> (i32, i32) {
         match self {
             MoveDirection::North => (0, 1),
             MoveDirection::South => (0, -1),
             MoveDirection::East => (1, 0),
             MoveDirection::West => (-1, 0),
+            MoveDirection::NorthEast => (1, 1),
+            MoveDirection::NorthWest => (-1, 1),
+            MoveDirection::SouthEast => (1, -1),
+            MoveDirection::SouthWest => (-1, -1),
         }
     }
 
-    pub fn all_directions() -> [MoveDirection; 4] {
+    /// The direction of a single step, if `delta` is one
+    pub fn from_delta(delta: IVec2) -> Option<MoveDirection> {
+        MoveDirection::ALL_DIRECTIONS.into_iter().find(|direction| IVec2::from(*direction) == delta)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        let (dx, dy) = self.delta();
+        dx != 0 && dy != 0
+    }
+
+    pub fn all_directions() -> [MoveDirection; 8] {
         MoveDirection::ALL_DIRECTIONS
     }
 
-    pub fn random_direction() -> MoveDirection {
-        let mut rng = fastrand::Rng::new();
+    pub fn random_direction(rng: &mut fastrand::Rng) -> MoveDirection {
         MoveDirection::ALL_DIRECTIONS[rng.usize(0..MoveDirection::ALL_DIRECTIONS.len())]
     }
 }
@@ -54,11 +91,7 @@ impl Add<MoveDirection> for Position {
 
 impl From<MoveDirection> for IVec2 {
     fn from(direction: MoveDirection) -> Self {
-        match direction {
-            MoveDirection::North => IVec2::new(0, 1),
-            MoveDirection::South => IVec2::new(0, -1),
-            MoveDirection::East => IVec2::new(1, 0),
-            MoveDirection::West => IVec2::new(-1, 0),
-        }
+        let (dx, dy) = direction.delta();
+        IVec2::new(dx, dy)
     }
 }
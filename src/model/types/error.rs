@@ -1,3 +1,4 @@
+use bevy::prelude::*;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -6,8 +7,18 @@ pub enum GameError {
     InvalidPosition,
     #[error("Terrain blocked")]
     TerrainBlocked,
+    #[error("Diagonal move not allowed")]
+    DiagonalBlocked,
+    #[error("Tile occupied by {0}")]
+    TileOccupied(Entity),
     #[error("Entity not found")]
     EntityNotFound,
     #[error("Missing component")]
     MissingComponent,
+    #[error("Invalid target")]
+    InvalidTarget,
+    #[error("No stairs here")]
+    NoStairs,
+    #[error("Cannot rest right now")]
+    CannotRest,
 }
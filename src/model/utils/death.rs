@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+use crate::model::{
+    components::{DeadTag, Description, MapLayer, Renderable, TurnActor},
+    resources::CurrentMap,
+};
+
+/// Turns an entity into a corpse: it stops taking turns, no longer blocks its tile and is drawn
+/// as a `%`. The turn queue drops it on its next cleanup.
+pub fn kill_entity(world: &mut World, entity: Entity) {
+    let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+
+    if let Some(mut actor) = entity_mut.get_mut::<TurnActor>() {
+        actor.alive = false;
+        actor.actions.clear();
+    }
+
+    if let Some(mut renderable) = entity_mut.get_mut::<Renderable>() {
+        renderable.glyph = '%';
+        renderable.color = Color::srgb(0.6, 0.0, 0.0); // #990000
+    }
+
+    entity_mut.insert((DeadTag, MapLayer::Item, Description::new("Corpse")));
+
+    let position = world.resource::<CurrentMap>().entities.location(entity).map(|(position, _)| position);
+    if let Some(position) = position {
+        world.resource_mut::<CurrentMap>().entities.insert(entity, position, MapLayer::Item);
+    }
+}
@@ -0,0 +1,336 @@
+use bevy::{ecs::system::SystemState, prelude::*};
+
+use crate::model::{
+    components::{
+        AITag, CombatStats, DeadTag, Description, Health, ItemTag, MapLayer, Position, Renderable,
+        TerrainType, TurnActor,
+    },
+    generation::{generator_for_depth, DungeonGenerator},
+    resources::{CurrentMap, Dungeon, FovMap, GameRng, StoredItem, StoredLevel, StoredMonster},
+    types::GameError,
+    utils::{kill_entity, spawn_generated, spawn_item, spawn_monster, spawn_monsters},
+    ModelConstants,
+};
+
+/// The actors that left and joined the level when the player changed level, so their turns can
+/// be unscheduled and scheduled right away
+#[derive(Resource, Default, Debug)]
+pub struct LevelChange {
+    pub despawned: Vec<Entity>,
+    pub spawned: Vec<Entity>,
+}
+
+/// Moves the player to the level at `depth`, storing the current level so it can be restored
+/// later. The level at `depth` is restored if it was visited before and generated otherwise.
+/// The player arrives on the first `arrival` tile of the level, such as the stairs leading back.
+///
+/// Returns the actors despawned with the old level and spawned with the new one.
+pub fn change_level(
+    world: &mut World,
+    player: Entity,
+    depth: u32,
+    arrival: TerrainType,
+) -> Result<LevelChange, GameError> {
+    let mut stored_level = world.resource_mut::<Dungeon>().take_level(depth);
+    let (terrain, spawns) = match &stored_level {
+        Some(level) => (level.terrain.clone(), Vec::new()),
+        None => {
+            let (width, height) = (ModelConstants::MAP_WIDTH, ModelConstants::MAP_HEIGHT);
+            let mut generator = generator_for_depth(depth, width, height);
+            let terrain = generator.generate(world.resource_mut::<GameRng>().map_gen());
+            (terrain, generator.spawns().to_vec())
+        }
+    };
+
+    // Arrive on the matching stairs, or anywhere walkable if the level has none
+    let arrival_position = DungeonGenerator::find_terrain(&terrain, arrival).or_else(|| {
+        terrain
+            .iter_with_positions()
+            .find(|(_, terrain_type)| !terrain_type.blocks_movement())
+            .map(|(position, _)| position)
+    });
+
+    // Only tear down the current level once the player has somewhere to go
+    let Some((x, y)) = arrival_position else {
+        if let Some(level) = stored_level {
+            world.resource_mut::<Dungeon>().store_level(depth, level);
+        }
+        return Err(GameError::InvalidPosition);
+    };
+    let arrival_position = Position::new(x, y);
+
+    let current_depth = world.resource::<Dungeon>().depth();
+    let (current_level, despawned) = despawn_level(world);
+    world.resource_mut::<Dungeon>().store_level(current_depth, current_level);
+
+    let mut state: SystemState<(Commands, Res<AssetServer>, ResMut<GameRng>)> = SystemState::new(world);
+    let (mut commands, asset_server, mut rng) = state.get_mut(world);
+
+    let terrain_entities = DungeonGenerator::generate_entities(&mut commands, &terrain);
+    let spawned: Vec<(Entity, bool)> = match &mut stored_level {
+        Some(level) => {
+            let mut spawned = Vec::with_capacity(level.monsters.len() + level.items.len());
+            for monster in level.monsters.drain(..) {
+                // Replace the freshly spawned state with the state the monster was left in
+                let entity = spawn_monster(&mut commands, &asset_server, monster.position);
+                let mut entity_commands = commands.entity(entity);
+                if let Some(health) = monster.health {
+                    entity_commands.insert(health);
+                }
+                if let Some(combat_stats) = monster.combat_stats {
+                    entity_commands.insert(combat_stats);
+                }
+                if let Some(turn_actor) = monster.turn_actor {
+                    entity_commands.insert(turn_actor);
+                }
+                spawned.push((entity, monster.dead));
+            }
+            for item in &level.items {
+                let entity = spawn_item(&mut commands, &asset_server, item.position, &item.name, item.glyph);
+                spawned.push((entity, false));
+            }
+            spawned
+        }
+        None => {
+            // Random monsters keep clear of the arrival tile and of the generated spawns
+            let avoid: Vec<Position> = spawns
+                .iter()
+                .map(|spawn| Position::new(spawn.position.0, spawn.position.1))
+                .chain([arrival_position])
+                .collect();
+            let count = depth as usize;
+            let mut entities =
+                spawn_monsters(&mut commands, &asset_server, &terrain, count, &avoid, rng.map_gen());
+            entities.extend(spawn_generated(&mut commands, &asset_server, &spawns));
+            entities.into_iter().map(|entity| (entity, false)).collect()
+        }
+    };
+    state.apply(world);
+
+    // Kill monsters that were already dead, leaving their corpses where they fell
+    for &(entity, dead) in &spawned {
+        let position = world.get::<Position>(entity).copied();
+        if let (Some(position), Some(&layer)) = (position, world.get::<MapLayer>(entity)) {
+            world.resource_mut::<CurrentMap>().entities.insert(entity, position, layer);
+        }
+
+        if dead {
+            kill_entity(world, entity);
+        }
+    }
+
+    let mut map = world.resource_mut::<CurrentMap>();
+    map.terrain = terrain_entities;
+    map.rebuild_masks(&terrain);
+    map.entities.insert(player, arrival_position, MapLayer::Actor);
+
+    let fov_map = stored_level.map_or_else(
+        || FovMap::new(ModelConstants::MAP_WIDTH, ModelConstants::MAP_HEIGHT),
+        |level| level.fov_map,
+    );
+    world.insert_resource(fov_map);
+    world.resource_mut::<Dungeon>().set_depth(depth);
+
+    if let Some(mut position) = world.get_mut::<Position>(player) {
+        *position = arrival_position;
+    }
+
+    log::info!("Entered level {} at {:?}", depth, arrival_position);
+
+    let spawned = spawned.into_iter().map(|(entity, _)| entity);
+    let spawned = spawned.filter(|&entity| world.get::<TurnActor>(entity).is_some()).collect();
+    Ok(LevelChange { despawned, spawned })
+}
+
+// Despawns the terrain, monsters and items of the current level, returning what is needed to
+// restore it and the despawned monsters
+fn despawn_level(world: &mut World) -> (StoredLevel, Vec<Entity>) {
+    let terrain_entities = world.resource::<CurrentMap>().terrain.clone();
+    let terrain =
+        terrain_entities.map(|entity| world.get::<TerrainType>(*entity).cloned().unwrap_or_default());
+
+    let mut q_monsters = world.query_filtered::<(Entity, &Position, Has<DeadTag>), With<AITag>>();
+    let found: Vec<(Entity, Position, bool)> =
+        q_monsters.iter(world).map(|(entity, position, dead)| (entity, *position, dead)).collect();
+
+    // Move the components out rather than cloning them, as queued actions cannot be cloned
+    let (monster_entities, monsters): (Vec<_>, Vec<_>) = found
+        .into_iter()
+        .map(|(entity, position, dead)| {
+            let mut entity_mut = world.entity_mut(entity);
+            let monster = StoredMonster {
+                position,
+                health: entity_mut.take::<Health>(),
+                combat_stats: entity_mut.take::<CombatStats>(),
+                turn_actor: entity_mut.take::<TurnActor>(),
+                dead,
+            };
+            (entity, monster)
+        })
+        .unzip();
+
+    let mut q_items = world.query_filtered::<(Entity, &Position, &Description, &Renderable), With<ItemTag>>();
+    let (item_entities, items): (Vec<_>, Vec<_>) = q_items
+        .iter(world)
+        .map(|(entity, position, description, renderable)| {
+            (entity, StoredItem { position: *position, name: description.0.clone(), glyph: renderable.glyph })
+        })
+        .unzip();
+
+    for entity in terrain_entities.iter().chain(&monster_entities).chain(&item_entities) {
+        world.despawn(*entity);
+    }
+    world.resource_mut::<CurrentMap>().entities.clear();
+
+    let level = StoredLevel { terrain, fov_map: world.resource::<FovMap>().clone(), monsters, items };
+    (level, monster_entities)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::AssetPlugin;
+    use brtk::grid::Grid;
+
+    use super::*;
+    use crate::model::{
+        actions::WaitBuilder,
+        components::PlayerTag,
+        types::GameActionBuilder,
+    };
+
+    /// Position, health, attack and defense, speed, queued actions, next turn time and whether
+    /// the monster is dead
+    type MonsterState = (Position, Option<(i32, i32)>, Option<(i32, i32)>, Option<(u64, usize, u64)>, bool);
+
+    // A world on the first level: an open room, a wounded monster and a dead one
+    fn setup_world() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+        app.init_asset::<Font>();
+        app.insert_resource(GameRng::new(7));
+        app.init_resource::<CurrentMap>();
+        app.init_resource::<FovMap>();
+        app.init_resource::<Dungeon>();
+
+        let world = app.world_mut();
+        let player = world.spawn((PlayerTag, Position::new(1, 1), MapLayer::Actor)).id();
+        world.resource_mut::<CurrentMap>().entities.insert(player, Position::new(1, 1), MapLayer::Actor);
+
+        let mut state: SystemState<(Commands, Res<AssetServer>)> = SystemState::new(world);
+        let (mut commands, asset_server) = state.get_mut(world);
+        let wounded = spawn_monster(&mut commands, &asset_server, Position::new(4, 2));
+        let dead = spawn_monster(&mut commands, &asset_server, Position::new(6, 3));
+        state.apply(world);
+
+        world.get_mut::<Health>(wounded).unwrap().take_damage(4);
+        *world.get_mut::<CombatStats>(wounded).unwrap() = CombatStats::new(5, 2);
+        let mut turn_actor = world.get_mut::<TurnActor>(wounded).unwrap();
+        turn_actor.speed = 80;
+        turn_actor.next_turn_time = 250;
+        turn_actor.add_action(WaitBuilder::new().with_entity(wounded).build());
+        for monster in [wounded, dead] {
+            let position = *world.get::<Position>(monster).unwrap();
+            world.resource_mut::<CurrentMap>().entities.insert(monster, position, MapLayer::Actor);
+        }
+        kill_entity(world, dead);
+
+        let mut fov_map = world.resource_mut::<FovMap>();
+        for x in 0..8 {
+            fov_map.set_revealed(Position::new(x, 2), true);
+        }
+
+        (app, player)
+    }
+
+    fn terrain(world: &World) -> Grid<TerrainType> {
+        let map = world.resource::<CurrentMap>();
+        map.terrain.map(|&entity| world.get::<TerrainType>(entity).unwrap().clone())
+    }
+
+    fn revealed(world: &World) -> Vec<bool> {
+        let fov_map = world.resource::<FovMap>();
+        let (width, height) = (ModelConstants::MAP_WIDTH as i32, ModelConstants::MAP_HEIGHT as i32);
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| Position::new(x, y)))
+            .map(|position| fov_map.is_revealed(position))
+            .collect()
+    }
+
+    fn monsters(world: &mut World) -> Vec<MonsterState> {
+        let mut q_monsters = world.query_filtered::<(
+            &Position,
+            Option<&Health>,
+            Option<&CombatStats>,
+            Option<&TurnActor>,
+            Has<DeadTag>,
+        ), With<AITag>>();
+        let mut monsters: Vec<MonsterState> = q_monsters
+            .iter(world)
+            .map(|(position, health, combat_stats, turn_actor, dead)| {
+                (
+                    *position,
+                    health.map(|health| (health.current, health.max)),
+                    combat_stats.map(|stats| (stats.attack, stats.defense)),
+                    turn_actor.map(|actor| (actor.speed, actor.actions.len(), actor.next_turn_time)),
+                    dead,
+                )
+            })
+            .collect();
+        monsters.sort_by_key(|(position, ..)| (position.x(), position.y()));
+        monsters
+    }
+
+    #[test]
+    fn descending_and_ascending_restores_the_level() {
+        let (mut app, player) = setup_world();
+        let world = app.world_mut();
+        let (terrain_before, revealed_before) = (terrain(world), revealed(world));
+        let monsters_before = monsters(world);
+        assert_eq!(monsters_before.len(), 2);
+
+        change_level(world, player, 2, TerrainType::UpStairs).unwrap();
+        assert_eq!(world.resource::<Dungeon>().depth(), 2);
+        assert!(world.resource::<Dungeon>().has_level(1));
+        assert!(revealed(world).iter().all(|&revealed| !revealed));
+
+        change_level(world, player, 1, TerrainType::DownStairs).unwrap();
+        assert_eq!(world.resource::<Dungeon>().depth(), 1);
+        assert!(world.resource::<Dungeon>().has_level(2));
+
+        assert_eq!(terrain(world).to_text(TerrainType::glyph), terrain_before.to_text(TerrainType::glyph));
+        assert_eq!(revealed(world), revealed_before);
+        assert_eq!(monsters(world), monsters_before);
+
+        let map = world.resource::<CurrentMap>();
+        let arrival = *world.get::<Position>(player).unwrap();
+        assert_eq!(map.get_actor(arrival), Some(player));
+        assert!(map.get_actor(Position::new(4, 2)).is_some());
+        assert!(map.get_actor(Position::new(6, 3)).is_none());
+    }
+
+    #[test]
+    fn a_level_without_an_arrival_tile_keeps_the_current_one() {
+        let (mut app, player) = setup_world();
+        let world = app.world_mut();
+        let terrain_before = terrain(world);
+        let monsters_before = monsters(world);
+
+        let size = (ModelConstants::MAP_WIDTH, ModelConstants::MAP_HEIGHT);
+        let walls = StoredLevel {
+            terrain: Grid::new_fill(size, TerrainType::Wall),
+            fov_map: FovMap::new(size.0, size.1),
+            monsters: Vec::new(),
+            items: Vec::new(),
+        };
+        world.resource_mut::<Dungeon>().store_level(2, walls);
+
+        let result = change_level(world, player, 2, TerrainType::UpStairs);
+        assert!(matches!(result, Err(GameError::InvalidPosition)));
+
+        assert_eq!(world.resource::<Dungeon>().depth(), 1);
+        assert!(world.resource::<Dungeon>().has_level(2));
+        assert_eq!(terrain(world).to_text(TerrainType::glyph), terrain_before.to_text(TerrainType::glyph));
+        assert_eq!(monsters(world), monsters_before);
+        assert_eq!(*world.get::<Position>(player).unwrap(), Position::new(1, 1));
+    }
+}
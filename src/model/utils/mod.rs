@@ -1,2 +1,6 @@
+mod death;
+pub use self::death::*;
+mod level;
+pub use self::level::*;
 mod spawner;
 pub use self::spawner::*;
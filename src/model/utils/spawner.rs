@@ -1,8 +1,13 @@
 use bevy::prelude::*;
+use brtk::prelude::*;
 
 use crate::{
     model::{
-        components::{Position, Renderable},
+        components::{
+            AITag, CombatStats, Description, Health, ItemTag, MapLayer, Position, Renderable, TerrainType,
+            TurnActor,
+        },
+        generation::{Spawn, SpawnKind},
         model_constants::ModelConstants,
     },
     view::ViewConstants,
@@ -54,3 +59,93 @@ pub fn spawn_ascii_entity(
 
     entity
 }
+
+// Helper function to spawn a monster
+pub fn spawn_monster(commands: &mut Commands, asset_server: &Res<AssetServer>, position: Position) -> Entity {
+    let monster_id = spawn_ascii_entity(
+        commands,
+        asset_server,
+        Some(position),
+        Renderable {
+            glyph: 'E',
+            color: Color::srgb(1.0, 0.0, 0.0), // #ff0000
+        },
+        1.0,
+    );
+
+    commands.entity(monster_id).insert((
+        AITag,
+        MapLayer::Actor,
+        TurnActor::new(120),
+        Health::new(10),
+        CombatStats::new(3, 1),
+    ));
+
+    monster_id
+}
+
+// Helper function to spawn an item lying on the map
+pub fn spawn_item(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    position: Position,
+    name: &str,
+    glyph: char,
+) -> Entity {
+    let item_id = spawn_ascii_entity(
+        commands,
+        asset_server,
+        Some(position),
+        Renderable {
+            glyph,
+            color: Color::srgb(1.0, 0.84, 0.0), // #ffd700
+        },
+        0.5,
+    );
+
+    commands.entity(item_id).insert((ItemTag, MapLayer::Item, Description::new(name)));
+
+    item_id
+}
+
+// Helper function to spawn the monsters and items a generator asked for
+pub fn spawn_generated(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    spawns: &[Spawn],
+) -> Vec<Entity> {
+    spawns
+        .iter()
+        .map(|spawn| {
+            let position = Position::new(spawn.position.0, spawn.position.1);
+            match &spawn.kind {
+                SpawnKind::Monster => spawn_monster(commands, asset_server, position),
+                SpawnKind::Item { name, glyph } => spawn_item(commands, asset_server, position, name, *glyph),
+            }
+        })
+        .collect()
+}
+
+// Helper function to spawn `count` monsters on random free floor tiles, away from `avoid`
+pub fn spawn_monsters(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    terrain: &Grid<TerrainType>,
+    count: usize,
+    avoid: &[Position],
+    rng: &mut fastrand::Rng,
+) -> Vec<Entity> {
+    let mut candidates: Vec<Position> = terrain
+        .iter_with_positions()
+        .filter(|(_, terrain_type)| **terrain_type == TerrainType::Floor)
+        .map(|((x, y), _)| Position::new(x, y))
+        .filter(|position| !avoid.contains(position))
+        .collect();
+    rng.shuffle(&mut candidates);
+
+    candidates
+        .into_iter()
+        .take(count)
+        .map(|position| spawn_monster(commands, asset_server, position))
+        .collect()
+}
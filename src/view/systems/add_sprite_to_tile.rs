@@ -32,13 +32,7 @@ pub fn add_sprite_to_tile(
 
         let text_style = TextFont { font: font.clone(), font_size: 25.0, ..default() };
 
-        let renderable = match tile_type {
-            TerrainType::Floor => Renderable { glyph: '.', color: Color::srgb(0.5, 0.5, 0.5) }, /* #808080 */
-            TerrainType::Wall => Renderable { glyph: '#', color: Color::srgb(0.7, 0.7, 0.7) },  /* #b3b3b3 */
-            TerrainType::Door => Renderable { glyph: '+', color: Color::srgb(0.65, 0.4, 0.1) }, /* #a66719 */
-            TerrainType::UpStairs => Renderable { glyph: '<', color: Color::srgb(1.0, 1.0, 0.0) }, /* #ffff00 */
-            TerrainType::DownStairs => Renderable { glyph: '>', color: Color::srgb(1.0, 1.0, 0.0) }, /* #ffff00 */
-        };
+        let renderable = terrain_renderable(tile_type);
 
         commands.entity(entity).insert((
             text_style,
@@ -50,3 +44,21 @@ pub fn add_sprite_to_tile(
         ));
     }
 }
+
+/// Keeps the glyph of a tile in sync when its terrain changes, such as a door opening
+pub fn update_tile_renderable(mut q_tiles: Query<(&TerrainType, &mut Renderable), Changed<TerrainType>>) {
+    for (tile_type, mut renderable) in &mut q_tiles {
+        *renderable = terrain_renderable(tile_type);
+    }
+}
+
+fn terrain_renderable(tile_type: &TerrainType) -> Renderable {
+    match tile_type {
+        TerrainType::Floor => Renderable { glyph: '.', color: Color::srgb(0.5, 0.5, 0.5) }, /* #808080 */
+        TerrainType::Wall => Renderable { glyph: '#', color: Color::srgb(0.7, 0.7, 0.7) },  /* #b3b3b3 */
+        TerrainType::Door => Renderable { glyph: '+', color: Color::srgb(0.65, 0.4, 0.1) }, /* #a66719 */
+        TerrainType::OpenDoor => Renderable { glyph: '\'', color: Color::srgb(0.65, 0.4, 0.1) }, /* #a66719 */
+        TerrainType::UpStairs => Renderable { glyph: '<', color: Color::srgb(1.0, 1.0, 0.0) }, /* #ffff00 */
+        TerrainType::DownStairs => Renderable { glyph: '>', color: Color::srgb(1.0, 1.0, 0.0) }, /* #ffff00 */
+    }
+}
@@ -6,3 +6,6 @@ pub use self::position_to_transform::*;
 
 mod sprite_visibility;
 pub use self::sprite_visibility::*;
+
+mod sync_renderable;
+pub use self::sync_renderable::*;
@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+use crate::model::components::Renderable;
+
+/// Keeps the drawn glyph and color in sync when a `Renderable` changes, such as an actor turning
+/// into a corpse
+pub fn sync_renderable(mut query: Query<(&Renderable, &mut Text2d, &mut TextColor), Changed<Renderable>>) {
+    for (renderable, mut text, mut color) in &mut query {
+        text.0 = renderable.glyph.to_string();
+        color.0 = renderable.color;
+    }
+}
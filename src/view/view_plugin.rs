@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use super::systems::{add_sprite_to_tile, position_to_transform, update_sprite_visibility};
+use super::systems::{
+    add_sprite_to_tile, position_to_transform, sync_renderable, update_sprite_visibility,
+    update_tile_renderable,
+};
 use crate::{AppSet, RunningState};
 
 pub struct ViewPlugin;
@@ -16,7 +19,11 @@ impl Plugin for ViewPlugin {
         app.add_systems(
             PostUpdate,
             (
-                (add_sprite_to_tile, update_sprite_visibility),
+                (
+                    add_sprite_to_tile,
+                    (update_tile_renderable, sync_renderable).chain(),
+                    update_sprite_visibility,
+                ),
                 position_to_transform.in_set(AppSet::Render).run_if(not(in_state(RunningState::Paused))),
             ),
         );